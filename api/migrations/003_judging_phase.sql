@@ -0,0 +1,3 @@
+-- Support Codeforces-style pretest/system test judging
+ALTER TABLE test_cases ADD COLUMN is_pretest BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE submissions ADD COLUMN judging_phase VARCHAR(20);
@@ -0,0 +1,2 @@
+-- Record which judger node handled each submission
+ALTER TABLE submissions ADD COLUMN judge_node_id VARCHAR(255);
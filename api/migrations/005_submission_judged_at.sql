@@ -0,0 +1,2 @@
+-- Track when judging finished to estimate queue latency
+ALTER TABLE submissions ADD COLUMN judged_at TIMESTAMP WITH TIME ZONE;
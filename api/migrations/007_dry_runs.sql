@@ -0,0 +1,16 @@
+-- Judging runs that are not submissions: the judger writes their outcome here
+-- and never touches submissions or submission_results
+CREATE TABLE dry_runs (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    problem_id UUID NOT NULL REFERENCES problems(id) ON DELETE CASCADE,
+    language_id UUID NOT NULL REFERENCES languages(id),
+    requested_by UUID NOT NULL REFERENCES users(id),
+    source_code TEXT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'Queued',
+    verdict VARCHAR(30),
+    execution_time_ms INTEGER,
+    execution_memory_kb INTEGER,
+    test_results JSONB NOT NULL DEFAULT '[]',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    judged_at TIMESTAMP WITH TIME ZONE
+);
@@ -0,0 +1,3 @@
+-- Track when a judger picked a submission up, so judging time can be measured
+-- separately from time spent waiting in the queue
+ALTER TABLE submissions ADD COLUMN judging_started_at TIMESTAMP WITH TIME ZONE;
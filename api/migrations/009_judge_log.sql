@@ -0,0 +1,4 @@
+-- Explanation shown to the user when a verdict needs more context than the
+-- verdict itself, e.g. a language the problem does not allow
+ALTER TABLE submissions ADD COLUMN judge_log TEXT;
+ALTER TABLE dry_runs ADD COLUMN judge_log TEXT;
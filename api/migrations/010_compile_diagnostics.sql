@@ -0,0 +1,8 @@
+-- Structured compiler diagnostics (file line/column and message) of the
+-- latest compilation, for frontends to annotate the submitted source
+ALTER TABLE submissions ADD COLUMN compile_diagnostics JSONB NOT NULL DEFAULT '[]';
+
+-- Raw compiler output, kept for logs the diagnostics parser cannot handle
+-- (linker errors, languages without a parser), or a note that a cached
+-- compilation was reused
+ALTER TABLE submissions ADD COLUMN compile_log TEXT;
@@ -8,6 +8,13 @@ pub struct Config {
     pub redis_url: String,
     pub rabbitmq_url: String,
     pub jwt_secret: String,
+    pub max_contest_duration_secs: i64,
+    /// Verdict strings counted as accepted in addition to the ones
+    /// `Verdict` parses as `Accepted`.
+    pub accepted_verdict_aliases: Vec<String>,
+    /// Total number of jobs the judgers work on at once, used to estimate
+    /// queue wait times.
+    pub judger_concurrency: usize,
 }
 
 impl Config {
@@ -27,12 +34,37 @@ impl Config {
         let server_address = std::env::var("SERVER_ADDRESS")
             .unwrap_or_else(|_| "0.0.0.0:5000".to_string());
 
+        let max_contest_duration_secs = std::env::var("MAX_CONTEST_DURATION_SECS")
+            .unwrap_or_else(|_| "1209600".to_string()) // 14 days
+            .parse()
+            .unwrap_or(1_209_600);
+
+        let accepted_verdict_aliases = match std::env::var("ACCEPTED_VERDICT_ALIASES") {
+            Ok(aliases) => aliases
+                .split(',')
+                .map(|alias| alias.trim().to_string())
+                .filter(|alias| !alias.is_empty())
+                .collect(),
+            Err(_) => shared::DEFAULT_ACCEPTED_VERDICT_ALIASES
+                .iter()
+                .map(|alias| alias.to_string())
+                .collect(),
+        };
+
+        let judger_concurrency = std::env::var("JUDGER_CONCURRENCY")
+            .unwrap_or_else(|_| "4".to_string())
+            .parse()
+            .unwrap_or(4);
+
         Ok(Config {
             server_address,
             database_url,
             redis_url,
             rabbitmq_url,
             jwt_secret,
+            max_contest_duration_secs,
+            accepted_verdict_aliases,
+            judger_concurrency,
         })
     }
 }
\ No newline at end of file
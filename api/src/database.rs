@@ -14,6 +14,11 @@ impl Database {
         Ok(Database { pool })
     }
 
+    #[cfg(test)]
+    pub fn from_pool(pool: PgPool) -> Self {
+        Database { pool }
+    }
+
     pub async fn migrate(&self) -> Result<()> {
         sqlx::migrate!("./migrations").run(&self.pool).await?;
         Ok(())
@@ -147,6 +152,18 @@ impl Database {
         Ok(problem)
     }
 
+    pub async fn update_problem_metadata(&self, id: Uuid, metadata: &serde_json::Value) -> Result<Problem> {
+        let problem = sqlx::query_as::<_, Problem>(
+            "UPDATE problems SET metadata = $1 WHERE id = $2 RETURNING *"
+        )
+        .bind(metadata)
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(problem)
+    }
+
     // Language operations
     pub async fn list_languages(&self) -> Result<Vec<Language>> {
         let languages = sqlx::query_as::<_, Language>(
@@ -186,7 +203,7 @@ impl Database {
         .bind(req.language_id)
         .bind(&req.source_code)
         .bind(chrono::Utc::now())
-        .bind("Queued")
+        .bind(JudgeStatus::Queued.as_db_str())
         .bind::<Option<String>>(None)
         .bind::<Option<i32>>(None)
         .bind::<Option<i32>>(None)
@@ -220,6 +237,105 @@ impl Database {
         Ok(submissions)
     }
 
+    /// Contest submissions whose current verdict came from pretest judging.
+    /// Submissions still being pretested are left out, since the judger
+    /// stamps the phase when it starts a job and a second job would race it.
+    pub async fn list_pretest_judged_contest_submissions(&self, contest_id: Uuid) -> Result<Vec<Submission>> {
+        let submissions = sqlx::query_as::<_, Submission>(
+            r#"
+            SELECT s.* FROM submissions s
+            JOIN problems p ON s.problem_id = p.id
+            WHERE p.contest_id = $1 AND s.judging_phase = $2 AND s.status = $3
+            ORDER BY s.submitted_at
+            "#,
+        )
+        .bind(contest_id)
+        .bind(JudgingPhase::Pretest.as_db_str())
+        .bind(JudgeStatus::Finished.as_db_str())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(submissions)
+    }
+
+    pub async fn update_submission_status(&self, id: Uuid, status: &str) -> Result<()> {
+        sqlx::query(
+            "UPDATE submissions SET status = $1 WHERE id = $2"
+        )
+        .bind(status)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn count_queued_submissions_before(&self, submitted_at: chrono::DateTime<chrono::Utc>) -> Result<i64> {
+        let count: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM submissions WHERE status = $1 AND submitted_at < $2"
+        )
+        .bind(JudgeStatus::Queued.as_db_str())
+        .bind(submitted_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count.0)
+    }
+
+    /// Average seconds a judger spent on each of the most recently judged
+    /// submissions, excluding time spent waiting in the queue.
+    pub async fn average_judging_duration_secs(&self, sample_size: i64) -> Result<Option<f64>> {
+        let avg: (Option<f64>,) = sqlx::query_as(
+            r#"
+            SELECT AVG(EXTRACT(EPOCH FROM (judged_at - judging_started_at)))::FLOAT8
+            FROM (
+                SELECT judging_started_at, judged_at FROM submissions
+                WHERE judged_at IS NOT NULL AND judging_started_at IS NOT NULL
+                ORDER BY judged_at DESC
+                LIMIT $1
+            ) recent
+            "#,
+        )
+        .bind(sample_size)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(avg.0)
+    }
+
+    // Dry run operations
+    pub async fn create_dry_run(&self, problem_id: Uuid, req: &DryRunRequest, requested_by: Uuid) -> Result<DryRun> {
+        let dry_run = sqlx::query_as::<_, DryRun>(
+            r#"
+            INSERT INTO dry_runs (id, problem_id, language_id, requested_by, source_code, status, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(problem_id)
+        .bind(req.language_id)
+        .bind(requested_by)
+        .bind(&req.source_code)
+        .bind(JudgeStatus::Queued.as_db_str())
+        .bind(chrono::Utc::now())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(dry_run)
+    }
+
+    pub async fn get_dry_run(&self, id: Uuid) -> Result<Option<DryRun>> {
+        let dry_run = sqlx::query_as::<_, DryRun>(
+            "SELECT * FROM dry_runs WHERE id = $1"
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(dry_run)
+    }
+
     // Contest operations
     pub async fn list_contests(&self) -> Result<Vec<Contest>> {
         let contests = sqlx::query_as::<_, Contest>(
@@ -267,6 +383,92 @@ impl Database {
         Ok(contest)
     }
 
+    /// Solve and attempt counts per contest problem. A submission counts as a
+    /// solve when its verdict is accepted according to
+    /// [`Verdict::is_accepted_with_aliases`].
+    pub async fn get_contest_problem_stats<S: AsRef<str>>(&self, contest_id: Uuid, accepted_aliases: &[S]) -> Result<Vec<ProblemSubmissionStats>> {
+        let verdicts: Vec<(String,)> = sqlx::query_as(
+            r#"
+            SELECT DISTINCT s.verdict
+            FROM submissions s
+            JOIN problems p ON s.problem_id = p.id
+            WHERE p.contest_id = $1 AND s.verdict IS NOT NULL
+            "#,
+        )
+        .bind(contest_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let accepted_verdicts: Vec<String> = verdicts
+            .into_iter()
+            .map(|(verdict,)| verdict)
+            .filter(|verdict| Verdict::is_accepted_with_aliases(verdict, accepted_aliases))
+            .collect();
+
+        let stats = sqlx::query_as::<_, ProblemSubmissionStats>(
+            r#"
+            SELECT
+                p.id AS problem_id,
+                COUNT(DISTINCT s.user_id) FILTER (WHERE s.verdict = ANY($2)) AS solve_count,
+                COUNT(s.id) AS attempt_count
+            FROM problems p
+            LEFT JOIN submissions s ON s.problem_id = p.id
+            WHERE p.contest_id = $1
+            GROUP BY p.id
+            "#,
+        )
+        .bind(contest_id)
+        .bind(accepted_verdicts)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(stats)
+    }
+
+    /// Submission counts per bucket index, where bucket `i` covers
+    /// `[start_time + i * bucket_seconds, start_time + (i + 1) * bucket_seconds)`.
+    /// Only submissions inside the contest window are counted.
+    pub async fn get_contest_submission_histogram(&self, contest_id: Uuid, bucket_seconds: i64) -> Result<Vec<(i64, i64)>> {
+        let buckets: Vec<(i64, i64)> = sqlx::query_as(
+            r#"
+            SELECT
+                FLOOR(EXTRACT(EPOCH FROM (s.submitted_at - c.start_time)) / $2)::BIGINT AS bucket,
+                COUNT(*) AS count
+            FROM submissions s
+            JOIN problems p ON s.problem_id = p.id
+            JOIN contests c ON p.contest_id = c.id
+            WHERE c.id = $1
+              AND s.submitted_at >= c.start_time
+              AND s.submitted_at < c.end_time
+            GROUP BY bucket
+            ORDER BY bucket
+            "#,
+        )
+        .bind(contest_id)
+        .bind(bucket_seconds as f64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(buckets)
+    }
+
+    pub async fn update_contest_end_time(&self, id: Uuid, end_time: chrono::DateTime<chrono::Utc>) -> Result<Contest> {
+        let contest = sqlx::query_as::<_, Contest>(
+            r#"
+            UPDATE contests
+            SET end_time = $1, duration = EXTRACT(EPOCH FROM ($1 - start_time))::INTEGER
+            WHERE id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(end_time)
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(contest)
+    }
+
     // Test case operations
     pub async fn get_test_cases(&self, problem_id: Uuid) -> Result<Vec<TestCase>> {
         let test_cases = sqlx::query_as::<_, TestCase>(
@@ -372,4 +574,40 @@ impl Database {
 
         Ok(contests)
     }
-}
\ No newline at end of file
+
+    // Audit log operations
+    pub async fn record_audit(&self, action: &str, actor_id: Uuid, target_id: Option<Uuid>, details: serde_json::Value) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO audit_log (id, action, actor_id, target_id, details, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(action)
+        .bind(actor_id)
+        .bind(target_id)
+        .bind(details)
+        .bind(chrono::Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Audit entries targeting a contest, including actions on its problems.
+    pub async fn list_contest_audit_log(&self, contest_id: Uuid) -> Result<Vec<AuditLogEntry>> {
+        let entries = sqlx::query_as::<_, AuditLogEntry>(
+            r#"
+            SELECT * FROM audit_log
+            WHERE target_id = $1 OR details->>'contest_id' = $1::text
+            ORDER BY created_at
+            "#,
+        )
+        .bind(contest_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+}
@@ -3,6 +3,7 @@ use bcrypt::{hash, verify, DEFAULT_COST};
 use shared::*;
 use serde_json::json;
 
+use super::audit;
 use crate::{auth::create_jwt, AppState};
 
 pub async fn register(
@@ -110,6 +111,8 @@ pub async fn register_admin(
         .await
         .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"message":"Failed to create user"}))))?;
 
+    audit(&state, "user.register_admin", requesting_user.id, Some(user.id), json!({"roles": user.roles})).await;
+
     // Create JWT token
     let token = create_jwt(user.id, &state.config.jwt_secret)
         .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"message":"Failed to create token"}))))?;
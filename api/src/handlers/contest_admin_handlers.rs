@@ -5,7 +5,9 @@ use axum::{
     Extension,
 };
 use uuid::Uuid;
+use serde_json::json;
 use shared::*;
+use super::audit;
 use crate::AppState;
 
 /// Assign a user as contest admin
@@ -54,6 +56,8 @@ pub async fn assign_contest_admin(
             }
         })?;
 
+    audit(&state, "contest_admin.assign", user.id, Some(req.contest_id), json!({"user_id": req.user_id})).await;
+
     Ok(Json(AssignContestAdminResponse {
         contest_admin_id: contest_admin.id,
         message: "Contest admin assigned successfully".to_string(),
@@ -87,6 +91,7 @@ pub async fn remove_contest_admin(
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
     if removed {
+        audit(&state, "contest_admin.remove", user.id, Some(contest_id), json!({"user_id": user_id})).await;
         Ok(StatusCode::NO_CONTENT)
     } else {
         Err(StatusCode::NOT_FOUND)
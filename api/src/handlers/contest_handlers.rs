@@ -1,11 +1,16 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
+    response::{IntoResponse, Response},
     Extension, Json,
 };
+use serde::{Deserialize, Serialize};
+use serde_json::json;
 use shared::*;
+use std::collections::HashMap;
 use uuid::Uuid;
 
+use super::audit;
 use crate::AppState;
 
 pub async fn list_contests(
@@ -34,34 +39,762 @@ pub async fn get_contest(
     Ok(Json(contest))
 }
 
+#[derive(Debug, Serialize)]
+pub struct FieldError {
+    pub field: &'static str,
+    pub message: String,
+}
+
+/// How far in the past a start time may lie, so that a contest created to
+/// start "now" survives request latency and clock skew.
+const START_TIME_GRACE_MINUTES: i64 = 5;
+
+fn validate_create_contest(req: &CreateContestRequest, max_duration_secs: i64) -> Vec<FieldError> {
+    let mut errors = Vec::new();
+
+    if req.title.trim().is_empty() {
+        errors.push(FieldError {
+            field: "title",
+            message: "Title must not be empty".to_string(),
+        });
+    }
+
+    if req.duration <= 0 {
+        errors.push(FieldError {
+            field: "duration",
+            message: "Duration must be positive so that end_time is after start_time".to_string(),
+        });
+    } else if req.duration as i64 > max_duration_secs {
+        errors.push(FieldError {
+            field: "duration",
+            message: format!("Duration must not exceed {} seconds", max_duration_secs),
+        });
+    }
+
+    if req.start_time < chrono::Utc::now() - chrono::Duration::minutes(START_TIME_GRACE_MINUTES) {
+        errors.push(FieldError {
+            field: "start_time",
+            message: format!("Start time must not be more than {} minutes in the past", START_TIME_GRACE_MINUTES),
+        });
+    }
+
+    errors
+}
+
+#[derive(Deserialize)]
+pub struct CreateContestQuery {
+    #[serde(default)]
+    dry_run: bool,
+}
+
+const SOON_START_WARNING_MINUTES: i64 = 15;
+
+fn create_contest_warnings(req: &CreateContestRequest) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    if req.start_time < chrono::Utc::now() + chrono::Duration::minutes(SOON_START_WARNING_MINUTES) {
+        warnings.push(format!(
+            "Contest starts within {} minutes; contestants may not see it in time",
+            SOON_START_WARNING_MINUTES
+        ));
+    }
+
+    warnings
+}
+
 pub async fn create_contest(
     State(state): State<AppState>,
     Extension(user): Extension<User>,
+    Query(query): Query<CreateContestQuery>,
     Json(payload): Json<CreateContestRequest>,
-) -> Result<Json<Contest>, StatusCode> {
+) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
     // Check if user is admin
     if !user.roles.contains(&"admin".to_string()) {
-        return Err(StatusCode::FORBIDDEN);
+        return Err((StatusCode::FORBIDDEN, Json(json!({"message":"Admin access required"}))));
+    }
+
+    let errors = validate_create_contest(&payload, state.config.max_contest_duration_secs);
+
+    // A dry run reports every problem without creating anything
+    if query.dry_run {
+        let warnings = create_contest_warnings(&payload);
+        return Ok(Json(json!({
+            "valid": errors.is_empty(),
+            "errors": errors,
+            "warnings": warnings,
+        }))
+        .into_response());
+    }
+
+    if !errors.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, Json(json!({"message":"Invalid contest", "errors": errors}))));
     }
 
     let contest = state
         .db
         .create_contest(&payload, user.id)
         .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"message":"Failed to create contest"}))))?;
 
-    Ok(Json(contest))
+    audit(&state, "contest.create", user.id, Some(contest.id), json!({"title": contest.title})).await;
+
+    Ok(Json(contest).into_response())
 }
 
 pub async fn get_contest_problems(
     State(state): State<AppState>,
     Path(contest_id): Path<Uuid>,
-) -> Result<Json<Vec<Problem>>, StatusCode> {
+) -> Result<Json<Vec<ContestProblemSummary>>, StatusCode> {
     let problems = state
         .db
         .list_problems(Some(contest_id))
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    Ok(Json(problems))
-}
\ No newline at end of file
+    let stats: HashMap<Uuid, ProblemSubmissionStats> = state
+        .db
+        .get_contest_problem_stats(contest_id, &state.config.accepted_verdict_aliases)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .into_iter()
+        .map(|s| (s.problem_id, s))
+        .collect();
+
+    let summaries = problems
+        .into_iter()
+        .map(|problem| {
+            let (solve_count, attempt_count) = stats
+                .get(&problem.id)
+                .map(|s| (s.solve_count, s.attempt_count))
+                .unwrap_or((0, 0));
+            ContestProblemSummary::new(problem, solve_count, attempt_count)
+        })
+        .collect();
+
+    Ok(Json(summaries))
+}
+
+#[derive(Deserialize)]
+pub struct SubmissionHistogramQuery {
+    bucket: Option<i64>,
+}
+
+const DEFAULT_HISTOGRAM_BUCKET_SECS: i64 = 60;
+const MAX_HISTOGRAM_BUCKETS: i64 = 10_000;
+
+/// Number of buckets covering a window of `window_seconds`, rounding up. A
+/// bucket at least as long as the window needs exactly one bucket, which also
+/// keeps the rounding from overflowing for huge bucket sizes.
+fn histogram_bucket_count(window_seconds: i64, bucket_seconds: i64) -> i64 {
+    if window_seconds <= 0 {
+        0
+    } else if bucket_seconds >= window_seconds {
+        1
+    } else {
+        (window_seconds + bucket_seconds - 1) / bucket_seconds
+    }
+}
+
+pub async fn get_submission_histogram(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(contest_id): Path<Uuid>,
+    Query(query): Query<SubmissionHistogramQuery>,
+) -> Result<Json<SubmissionHistogramResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let contest = state
+        .db
+        .get_contest(contest_id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"message":"Database error"}))))?
+        .ok_or((StatusCode::NOT_FOUND, Json(json!({"message":"Contest not found"}))))?;
+
+    let has_permission = user.roles.contains(&"admin".to_string())
+        || user.roles.contains(&"superadmin".to_string())
+        || state.db.is_contest_admin(contest_id, user.id)
+            .await
+            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"message":"Database error"}))))?;
+
+    if !has_permission {
+        return Err((StatusCode::FORBIDDEN, Json(json!({"message":"Contest admin access required"}))));
+    }
+
+    let bucket_seconds = query.bucket.unwrap_or(DEFAULT_HISTOGRAM_BUCKET_SECS);
+    if bucket_seconds <= 0 {
+        return Err((StatusCode::BAD_REQUEST, Json(json!({"message":"bucket must be a positive number of seconds"}))));
+    }
+
+    let window_seconds = contest.end_time.signed_duration_since(contest.start_time).num_seconds().max(0);
+    let bucket_count = histogram_bucket_count(window_seconds, bucket_seconds);
+    if bucket_count > MAX_HISTOGRAM_BUCKETS {
+        return Err((StatusCode::BAD_REQUEST, Json(json!({"message":"bucket is too small for the contest duration"}))));
+    }
+
+    let counts: HashMap<i64, i64> = state
+        .db
+        .get_contest_submission_histogram(contest_id, bucket_seconds)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"message":"Database error"}))))?
+        .into_iter()
+        .collect();
+
+    // Emit every bucket in the window, including empty ones
+    let buckets = (0..bucket_count)
+        .map(|i| SubmissionHistogramBucket {
+            start_time: contest.start_time + chrono::Duration::seconds(i * bucket_seconds),
+            count: counts.get(&i).copied().unwrap_or(0),
+        })
+        .collect();
+
+    Ok(Json(SubmissionHistogramResponse {
+        bucket_seconds,
+        buckets,
+    }))
+}
+
+pub async fn extend_contest(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(contest_id): Path<Uuid>,
+    Json(payload): Json<ExtendContestRequest>,
+) -> Result<Json<Contest>, (StatusCode, Json<serde_json::Value>)> {
+    let contest = state
+        .db
+        .get_contest(contest_id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"message":"Database error"}))))?
+        .ok_or((StatusCode::NOT_FOUND, Json(json!({"message":"Contest not found"}))))?;
+
+    let has_permission = user.roles.contains(&"admin".to_string())
+        || user.roles.contains(&"superadmin".to_string())
+        || contest.created_by == user.id
+        || state.db.is_contest_admin(contest_id, user.id)
+            .await
+            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"message":"Database error"}))))?;
+
+    if !has_permission {
+        return Err((StatusCode::FORBIDDEN, Json(json!({"message":"Contest admin access required"}))));
+    }
+
+    if payload.new_end_time <= chrono::Utc::now() {
+        return Err((StatusCode::BAD_REQUEST, Json(json!({"message":"new_end_time must be in the future"}))));
+    }
+
+    if payload.new_end_time <= contest.start_time {
+        return Err((StatusCode::BAD_REQUEST, Json(json!({"message":"new_end_time must be after the contest start time"}))));
+    }
+
+    let contest = state
+        .db
+        .update_contest_end_time(contest_id, payload.new_end_time)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"message":"Failed to update contest"}))))?;
+
+    audit(&state, "contest.extend", user.id, Some(contest_id), json!({"new_end_time": contest.end_time})).await;
+
+    Ok(Json(contest))
+}
+
+/// Re-judge every pretest-judged submission of a finished contest on the
+/// full test set.
+pub async fn run_system_tests(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(contest_id): Path<Uuid>,
+) -> Result<Json<SystemTestResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let contest = state
+        .db
+        .get_contest(contest_id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"message":"Database error"}))))?
+        .ok_or((StatusCode::NOT_FOUND, Json(json!({"message":"Contest not found"}))))?;
+
+    let has_permission = user.roles.contains(&"admin".to_string())
+        || user.roles.contains(&"superadmin".to_string())
+        || contest.created_by == user.id
+        || state.db.is_contest_admin(contest_id, user.id)
+            .await
+            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"message":"Database error"}))))?;
+
+    if !has_permission {
+        return Err((StatusCode::FORBIDDEN, Json(json!({"message":"Contest admin access required"}))));
+    }
+
+    if contest.end_time > chrono::Utc::now() {
+        return Err((StatusCode::CONFLICT, Json(json!({"message":"System tests can only run after the contest has ended"}))));
+    }
+
+    let submissions = state
+        .db
+        .list_pretest_judged_contest_submissions(contest_id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"message":"Database error"}))))?;
+
+    for submission in &submissions {
+        state
+            .db
+            .update_submission_status(submission.id, JudgeStatus::Queued.as_db_str())
+            .await
+            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"message":"Database error"}))))?;
+
+        let judging_job = JudgingJob {
+            submission_id: submission.id,
+            user_id: submission.user_id,
+            problem_id: submission.problem_id,
+            language_id: submission.language_id,
+            source_code: submission.source_code.clone(),
+            phase: JudgingPhase::SystemTest,
+            dry_run: false,
+        };
+
+        state
+            .queue
+            .publish_judging_job(&judging_job)
+            .await
+            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"message":"Failed to queue system test"}))))?;
+    }
+
+    audit(&state, "contest.system_test", user.id, Some(contest_id), json!({"queued_submissions": submissions.len()})).await;
+
+    Ok(Json(SystemTestResponse {
+        queued_submissions: submissions.len(),
+    }))
+}
+
+pub async fn get_contest_audit_log(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(contest_id): Path<Uuid>,
+) -> Result<Json<Vec<AuditLogEntry>>, StatusCode> {
+    let _contest = state
+        .db
+        .get_contest(contest_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let has_permission = user.roles.contains(&"admin".to_string())
+        || user.roles.contains(&"superadmin".to_string())
+        || state.db.is_contest_admin(contest_id, user.id)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if !has_permission {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let entries = state
+        .db
+        .list_contest_audit_log(contest_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::*;
+    use chrono::Duration;
+    use sqlx::PgPool;
+
+    fn contest_request(duration: i32) -> CreateContestRequest {
+        CreateContestRequest {
+            title: "Round 1".to_string(),
+            description: String::new(),
+            start_time: chrono::Utc::now() + Duration::days(1),
+            duration,
+        }
+    }
+
+    fn error_fields(errors: &[FieldError]) -> Vec<&'static str> {
+        errors.iter().map(|e| e.field).collect()
+    }
+
+    #[test]
+    fn validate_create_contest_accepts_a_valid_contest() {
+        assert!(validate_create_contest(&contest_request(3600), 7200).is_empty());
+        assert!(validate_create_contest(&contest_request(7200), 7200).is_empty());
+    }
+
+    #[test]
+    fn validate_create_contest_rejects_non_positive_duration() {
+        assert_eq!(error_fields(&validate_create_contest(&contest_request(0), 7200)), vec!["duration"]);
+        assert_eq!(error_fields(&validate_create_contest(&contest_request(-60), 7200)), vec!["duration"]);
+    }
+
+    #[test]
+    fn validate_create_contest_rejects_duration_over_max() {
+        let errors = validate_create_contest(&contest_request(7201), 7200);
+        assert_eq!(error_fields(&errors), vec!["duration"]);
+        assert!(errors[0].message.contains("7200"));
+    }
+
+    #[test]
+    fn validate_create_contest_reports_each_invalid_field() {
+        let mut req = contest_request(0);
+        req.title = "  ".to_string();
+        req.start_time = chrono::Utc::now() - Duration::hours(1);
+
+        let errors = validate_create_contest(&req, 7200);
+        assert_eq!(error_fields(&errors), vec!["title", "duration", "start_time"]);
+    }
+
+    #[test]
+    fn validate_create_contest_allows_a_start_time_just_in_the_past() {
+        let mut req = contest_request(3600);
+        req.start_time = chrono::Utc::now() - Duration::seconds(30);
+        assert!(validate_create_contest(&req, 7200).is_empty());
+
+        req.start_time = chrono::Utc::now() - Duration::minutes(START_TIME_GRACE_MINUTES + 1);
+        assert_eq!(error_fields(&validate_create_contest(&req, 7200)), vec!["start_time"]);
+    }
+
+    async fn json_body(response: Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    async fn count_contests(pool: &PgPool) -> i64 {
+        let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM contests")
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        count
+    }
+
+    #[sqlx::test]
+    async fn create_contest_dry_run_of_valid_contest_inserts_nothing(pool: PgPool) {
+        let (state, _queue) = test_state(pool.clone());
+        let admin = insert_user(&state.db, "admin", &["admin"]).await;
+
+        let response = create_contest(
+            State(state),
+            Extension(admin),
+            Query(CreateContestQuery { dry_run: true }),
+            Json(contest_request(3600)),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json_body(response).await;
+        assert_eq!(body["valid"], true);
+        assert_eq!(body["errors"], json!([]));
+        assert_eq!(count_contests(&pool).await, 0);
+    }
+
+    #[sqlx::test]
+    async fn create_contest_dry_run_of_invalid_contest_reports_field_errors(pool: PgPool) {
+        let (state, _queue) = test_state(pool.clone());
+        let admin = insert_user(&state.db, "admin", &["admin"]).await;
+        let mut req = contest_request(0);
+        req.title = String::new();
+
+        let response = create_contest(
+            State(state),
+            Extension(admin),
+            Query(CreateContestQuery { dry_run: true }),
+            Json(req),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json_body(response).await;
+        assert_eq!(body["valid"], false);
+        let fields: Vec<&str> = body["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["field"].as_str().unwrap())
+            .collect();
+        assert_eq!(fields, vec!["title", "duration"]);
+        assert_eq!(count_contests(&pool).await, 0);
+    }
+
+    #[sqlx::test]
+    async fn create_contest_without_dry_run_inserts_the_contest(pool: PgPool) {
+        let (state, _queue) = test_state(pool.clone());
+        let admin = insert_user(&state.db, "admin", &["admin"]).await;
+
+        let response = create_contest(
+            State(state),
+            Extension(admin),
+            Query(CreateContestQuery { dry_run: false }),
+            Json(contest_request(3600)),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(count_contests(&pool).await, 1);
+    }
+
+    #[test]
+    fn histogram_bucket_count_rounds_up_partial_buckets() {
+        assert_eq!(histogram_bucket_count(3600, 60), 60);
+        assert_eq!(histogram_bucket_count(3601, 60), 61);
+        assert_eq!(histogram_bucket_count(90, 60), 2);
+        assert_eq!(histogram_bucket_count(0, 60), 0);
+    }
+
+    #[test]
+    fn histogram_bucket_count_does_not_overflow_for_huge_buckets() {
+        assert_eq!(histogram_bucket_count(3600, i64::MAX), 1);
+        assert_eq!(histogram_bucket_count(i64::MAX, i64::MAX), 1);
+    }
+
+    async fn set_verdict(pool: &PgPool, submission_id: Uuid, verdict: &str) {
+        sqlx::query("UPDATE submissions SET verdict = $1, status = $2 WHERE id = $3")
+            .bind(verdict)
+            .bind(JudgeStatus::Finished.as_db_str())
+            .bind(submission_id)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    #[sqlx::test]
+    async fn contest_problems_report_solve_and_attempt_counts(pool: PgPool) {
+        let (state, _queue) = test_state(pool.clone());
+        let admin = insert_user(&state.db, "admin", &["admin"]).await;
+        let alice = insert_user(&state.db, "alice", &["contestant"]).await;
+        let bob = insert_user(&state.db, "bob", &["contestant"]).await;
+        let carol = insert_user(&state.db, "carol", &["contestant"]).await;
+        let contest = insert_contest(&state.db, admin.id, Duration::hours(-1), Duration::hours(1)).await;
+        let solved = insert_problem(&state.db, &pool, admin.id, Some(contest.id)).await;
+        let unsolved = insert_problem(&state.db, &pool, admin.id, Some(contest.id)).await;
+        let untried = insert_problem(&state.db, &pool, admin.id, Some(contest.id)).await;
+
+        // alice solves twice, bob's accepted verdict is stored as an alias
+        for (user, verdict) in [(&alice, "AC"), (&alice, "AC"), (&bob, "OK"), (&carol, "WA")] {
+            let submission = insert_submission(&state.db, &pool, user.id, solved.id).await;
+            set_verdict(&pool, submission.id, verdict).await;
+        }
+        let submission = insert_submission(&state.db, &pool, carol.id, unsolved.id).await;
+        set_verdict(&pool, submission.id, "WA").await;
+
+        let Json(summaries) = get_contest_problems(State(state), Path(contest.id)).await.unwrap();
+        let counts: HashMap<Uuid, (i64, i64)> = summaries
+            .iter()
+            .map(|s| (s.problem.id, (s.solve_count, s.attempt_count)))
+            .collect();
+
+        assert_eq!(counts[&solved.id], (2, 4));
+        assert_eq!(counts[&unsolved.id], (0, 1));
+        assert_eq!(counts[&untried.id], (0, 0));
+
+        let difficulty: HashMap<Uuid, f64> = summaries
+            .iter()
+            .map(|s| (s.problem.id, s.estimated_difficulty))
+            .collect();
+        assert!(difficulty[&unsolved.id] > difficulty[&solved.id]);
+    }
+
+    async fn set_submitted_at(pool: &PgPool, submission_id: Uuid, submitted_at: chrono::DateTime<chrono::Utc>) {
+        sqlx::query("UPDATE submissions SET submitted_at = $1 WHERE id = $2")
+            .bind(submitted_at)
+            .bind(submission_id)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    #[sqlx::test]
+    async fn submission_histogram_counts_submissions_at_known_minutes(pool: PgPool) {
+        let (state, _queue) = test_state(pool.clone());
+        let admin = insert_user(&state.db, "admin", &["admin"]).await;
+        let contestant = insert_user(&state.db, "contestant", &["contestant"]).await;
+        let contest = insert_contest(&state.db, admin.id, Duration::minutes(-60), Duration::minutes(-50)).await;
+        let problem = insert_problem(&state.db, &pool, admin.id, Some(contest.id)).await;
+
+        // Seconds after the start: minute 0 once, minute 1 twice, minute 5
+        // once, and one submission before the contest that is not counted
+        for offset in [30, 65, 119, 300, -10] {
+            let submission = insert_submission(&state.db, &pool, contestant.id, problem.id).await;
+            set_submitted_at(&pool, submission.id, contest.start_time + Duration::seconds(offset)).await;
+        }
+
+        let Json(histogram) = get_submission_histogram(
+            State(state.clone()),
+            Extension(admin.clone()),
+            Path(contest.id),
+            Query(SubmissionHistogramQuery { bucket: None }),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(histogram.bucket_seconds, 60);
+        let counts: Vec<i64> = histogram.buckets.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![1, 2, 0, 0, 0, 1, 0, 0, 0, 0]);
+        assert_eq!(histogram.buckets[5].start_time, contest.start_time + Duration::minutes(5));
+
+        let Json(histogram) = get_submission_histogram(
+            State(state),
+            Extension(admin),
+            Path(contest.id),
+            Query(SubmissionHistogramQuery { bucket: Some(i64::MAX) }),
+        )
+        .await
+        .unwrap();
+
+        let counts: Vec<i64> = histogram.buckets.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![4]);
+    }
+
+    async fn set_judging_phase(pool: &PgPool, submission_id: Uuid, phase: JudgingPhase) {
+        sqlx::query("UPDATE submissions SET judging_phase = $1, status = $2 WHERE id = $3")
+            .bind(phase.as_db_str())
+            .bind(JudgeStatus::Finished.as_db_str())
+            .bind(submission_id)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    #[sqlx::test]
+    async fn extend_contest_rejects_end_times_in_the_past_or_before_the_start(pool: PgPool) {
+        let (state, _queue) = test_state(pool.clone());
+        let admin = insert_user(&state.db, "admin", &["admin"]).await;
+        let running = insert_contest(&state.db, admin.id, Duration::hours(-1), Duration::hours(1)).await;
+        let upcoming = insert_contest(&state.db, admin.id, Duration::hours(1), Duration::hours(3)).await;
+
+        for (contest, new_end_time) in [
+            (&running, chrono::Utc::now() - Duration::minutes(10)),
+            (&upcoming, chrono::Utc::now() + Duration::minutes(30)),
+        ] {
+            let (status, _) = extend_contest(
+                State(state.clone()),
+                Extension(admin.clone()),
+                Path(contest.id),
+                Json(ExtendContestRequest { new_end_time }),
+            )
+            .await
+            .unwrap_err();
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+
+            let stored = state.db.get_contest(contest.id).await.unwrap().unwrap();
+            assert_eq!(stored.end_time, contest.end_time);
+            assert_eq!(stored.duration, contest.duration);
+        }
+    }
+
+    #[sqlx::test]
+    async fn submissions_in_the_extended_window_count_as_in_contest(pool: PgPool) {
+        let (state, queue) = test_state(pool.clone());
+        let admin = insert_user(&state.db, "admin", &["admin"]).await;
+        let contestant = insert_user(&state.db, "contestant", &["contestant"]).await;
+        let contest = insert_contest(&state.db, admin.id, Duration::hours(-3), Duration::hours(-1)).await;
+        let problem = insert_problem(&state.db, &pool, admin.id, Some(contest.id)).await;
+
+        let new_end_time = contest.end_time + Duration::hours(2);
+        let Json(extended) = extend_contest(
+            State(state.clone()),
+            Extension(admin.clone()),
+            Path(contest.id),
+            Json(ExtendContestRequest { new_end_time }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(extended.end_time, new_end_time);
+        assert_eq!(extended.duration, 4 * 60 * 60);
+
+        let Json(_) = crate::handlers::submission_handlers::submit_code(
+            State(state.clone()),
+            Extension(contestant),
+            Json(SubmissionRequest {
+                problem_id: problem.id,
+                language_id: any_language_id(&pool).await,
+                source_code: "print(1)".to_string(),
+            }),
+        )
+        .await
+        .unwrap();
+
+        let jobs = queue.jobs.lock().unwrap().clone();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].phase, JudgingPhase::Pretest);
+
+        let Json(histogram) = get_submission_histogram(
+            State(state),
+            Extension(admin),
+            Path(contest.id),
+            Query(SubmissionHistogramQuery { bucket: Some(60 * 60) }),
+        )
+        .await
+        .unwrap();
+        let counts: Vec<i64> = histogram.buckets.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![0, 0, 0, 1]);
+    }
+
+    #[sqlx::test]
+    async fn system_test_requeues_pretest_judged_submissions_on_the_full_set(pool: PgPool) {
+        let (state, queue) = test_state(pool.clone());
+        let admin = insert_user(&state.db, "admin", &["admin"]).await;
+        let contestant = insert_user(&state.db, "contestant", &["contestant"]).await;
+        let contest = insert_contest(&state.db, admin.id, Duration::hours(-3), Duration::hours(-1)).await;
+        let problem = insert_problem(&state.db, &pool, admin.id, Some(contest.id)).await;
+        let other_problem = insert_problem(&state.db, &pool, admin.id, None).await;
+
+        let pretested = insert_submission(&state.db, &pool, contestant.id, problem.id).await;
+        set_judging_phase(&pool, pretested.id, JudgingPhase::Pretest).await;
+        let system_tested = insert_submission(&state.db, &pool, contestant.id, problem.id).await;
+        set_judging_phase(&pool, system_tested.id, JudgingPhase::SystemTest).await;
+        let outside_contest = insert_submission(&state.db, &pool, contestant.id, other_problem.id).await;
+        set_judging_phase(&pool, outside_contest.id, JudgingPhase::Pretest).await;
+
+        let Json(response) = run_system_tests(State(state.clone()), Extension(admin), Path(contest.id))
+            .await
+            .unwrap();
+        assert_eq!(response.queued_submissions, 1);
+
+        let jobs = queue.jobs.lock().unwrap().clone();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].submission_id, pretested.id);
+        assert_eq!(jobs[0].phase, JudgingPhase::SystemTest);
+        assert!(!jobs[0].dry_run);
+
+        let requeued = state.db.get_submission(pretested.id).await.unwrap().unwrap();
+        assert_eq!(requeued.status, JudgeStatus::Queued.as_db_str());
+    }
+
+    #[sqlx::test]
+    async fn system_test_skips_submissions_still_being_pretested(pool: PgPool) {
+        let (state, queue) = test_state(pool.clone());
+        let admin = insert_user(&state.db, "admin", &["admin"]).await;
+        let contestant = insert_user(&state.db, "contestant", &["contestant"]).await;
+        let contest = insert_contest(&state.db, admin.id, Duration::hours(-3), Duration::hours(-1)).await;
+        let problem = insert_problem(&state.db, &pool, admin.id, Some(contest.id)).await;
+
+        let in_flight = insert_submission(&state.db, &pool, contestant.id, problem.id).await;
+        set_judging_phase(&pool, in_flight.id, JudgingPhase::Pretest).await;
+        state
+            .db
+            .update_submission_status(in_flight.id, JudgeStatus::Compiling.as_db_str())
+            .await
+            .unwrap();
+
+        let Json(response) = run_system_tests(State(state.clone()), Extension(admin), Path(contest.id))
+            .await
+            .unwrap();
+        assert_eq!(response.queued_submissions, 0);
+        assert!(queue.jobs.lock().unwrap().is_empty());
+
+        let untouched = state.db.get_submission(in_flight.id).await.unwrap().unwrap();
+        assert_eq!(untouched.status, JudgeStatus::Compiling.as_db_str());
+    }
+
+    #[sqlx::test]
+    async fn system_test_is_rejected_while_the_contest_is_running(pool: PgPool) {
+        let (state, queue) = test_state(pool.clone());
+        let admin = insert_user(&state.db, "admin", &["admin"]).await;
+        let contest = insert_contest(&state.db, admin.id, Duration::hours(-1), Duration::hours(1)).await;
+
+        let (status, _) = run_system_tests(State(state), Extension(admin), Path(contest.id))
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert!(queue.jobs.lock().unwrap().is_empty());
+    }
+}
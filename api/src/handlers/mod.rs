@@ -5,3 +5,179 @@ pub mod submission_handlers;
 pub mod contest_handlers;
 pub mod contest_admin_handlers;
 
+use uuid::Uuid;
+
+use crate::AppState;
+
+/// Record a privileged action in the audit log. Failing to write the entry
+/// is logged but never fails the action itself.
+pub(crate) async fn audit(state: &AppState, action: &str, actor_id: Uuid, target_id: Option<Uuid>, details: serde_json::Value) {
+    if let Err(e) = state.db.record_audit(action, actor_id, target_id, details).await {
+        tracing::warn!("Failed to record audit entry {}: {}", action, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::*;
+    use axum::{
+        extract::{Extension, Json, Path, Query, State},
+        http::StatusCode,
+    };
+    use chrono::Duration;
+    use shared::*;
+    use sqlx::PgPool;
+
+    async fn audit_entries(pool: &PgPool) -> Vec<AuditLogEntry> {
+        sqlx::query_as("SELECT * FROM audit_log ORDER BY created_at")
+            .fetch_all(pool)
+            .await
+            .unwrap()
+    }
+
+    /// Assert that exactly one entry was written, for `action` by `actor`.
+    async fn assert_single_entry(pool: &PgPool, action: &str, actor: &User, target_id: Uuid) {
+        let entries = audit_entries(pool).await;
+        assert_eq!(entries.len(), 1, "expected one audit entry, got {:?}", entries);
+        assert_eq!(entries[0].action, action);
+        assert_eq!(entries[0].actor_id, actor.id);
+        assert_eq!(entries[0].target_id, Some(target_id));
+    }
+
+    #[sqlx::test]
+    async fn create_contest_is_audited(pool: PgPool) {
+        let (state, _queue) = test_state(pool.clone());
+        let admin = insert_user(&state.db, "admin", &["admin"]).await;
+        let req = CreateContestRequest {
+            title: "Round 1".to_string(),
+            description: String::new(),
+            start_time: chrono::Utc::now() + Duration::days(1),
+            duration: 3600,
+        };
+
+        contest_handlers::create_contest(
+            State(state.clone()),
+            Extension(admin.clone()),
+            Query::try_from_uri(&"/api/contests".parse().unwrap()).unwrap(),
+            Json(req),
+        )
+        .await
+        .unwrap();
+
+        let contest = state.db.list_contests().await.unwrap().remove(0);
+        assert_single_entry(&pool, "contest.create", &admin, contest.id).await;
+    }
+
+    #[sqlx::test]
+    async fn extend_contest_is_audited_as_the_requesting_admin(pool: PgPool) {
+        let (state, _queue) = test_state(pool.clone());
+        let creator = insert_user(&state.db, "creator", &["admin"]).await;
+        let admin = insert_user(&state.db, "admin", &["admin"]).await;
+        let contest = insert_contest(&state.db, creator.id, Duration::hours(-1), Duration::hours(1)).await;
+
+        let Json(_) = contest_handlers::extend_contest(
+            State(state),
+            Extension(admin.clone()),
+            Path(contest.id),
+            Json(ExtendContestRequest { new_end_time: contest.end_time + Duration::hours(1) }),
+        )
+        .await
+        .unwrap();
+
+        assert_single_entry(&pool, "contest.extend", &admin, contest.id).await;
+    }
+
+    #[sqlx::test]
+    async fn contest_admin_assignment_and_removal_are_audited(pool: PgPool) {
+        let (state, _queue) = test_state(pool.clone());
+        let admin = insert_user(&state.db, "admin", &["admin"]).await;
+        let helper = insert_user(&state.db, "helper", &["contestant"]).await;
+        let contest = insert_contest(&state.db, admin.id, Duration::hours(1), Duration::hours(3)).await;
+
+        let Json(_) = contest_admin_handlers::assign_contest_admin(
+            State(state.clone()),
+            Extension(admin.clone()),
+            Json(AssignContestAdminRequest { contest_id: contest.id, user_id: helper.id }),
+        )
+        .await
+        .unwrap();
+        assert_single_entry(&pool, "contest_admin.assign", &admin, contest.id).await;
+
+        sqlx::query("DELETE FROM audit_log").execute(&pool).await.unwrap();
+        contest_admin_handlers::remove_contest_admin(State(state), Extension(admin.clone()), Path((contest.id, helper.id)))
+            .await
+            .unwrap();
+        assert_single_entry(&pool, "contest_admin.remove", &admin, contest.id).await;
+    }
+
+    #[sqlx::test]
+    async fn problem_creation_and_config_update_are_audited(pool: PgPool) {
+        let (state, _queue) = test_state(pool.clone());
+        let admin = insert_user(&state.db, "admin", &["admin"]).await;
+        let question_type_id = question_type_id(&pool, "ioi-standard").await;
+
+        let Json(problem) = problem_handlers::create_problem(
+            State(state.clone()),
+            Extension(admin.clone()),
+            Json(problem_request(question_type_id, None)),
+        )
+        .await
+        .unwrap();
+        assert_single_entry(&pool, "problem.create", &admin, problem.id).await;
+
+        sqlx::query("DELETE FROM audit_log").execute(&pool).await.unwrap();
+        let Json(_) = problem_handlers::update_comparison_config(
+            State(state),
+            Extension(admin.clone()),
+            Path(problem.id),
+            Json(ComparisonConfig::default()),
+        )
+        .await
+        .unwrap();
+        assert_single_entry(&pool, "problem.comparison_config.update", &admin, problem.id).await;
+    }
+
+    #[sqlx::test]
+    async fn system_test_is_audited(pool: PgPool) {
+        let (state, _queue) = test_state(pool.clone());
+        let admin = insert_user(&state.db, "admin", &["admin"]).await;
+        let contest = insert_contest(&state.db, admin.id, Duration::hours(-3), Duration::hours(-1)).await;
+
+        let Json(_) = contest_handlers::run_system_tests(State(state), Extension(admin.clone()), Path(contest.id))
+            .await
+            .unwrap();
+
+        assert_single_entry(&pool, "contest.system_test", &admin, contest.id).await;
+    }
+
+    #[sqlx::test]
+    async fn rejected_actions_are_not_audited(pool: PgPool) {
+        let (state, _queue) = test_state(pool.clone());
+        let admin = insert_user(&state.db, "admin", &["admin"]).await;
+        let contestant = insert_user(&state.db, "contestant", &["contestant"]).await;
+        let contest = insert_contest(&state.db, admin.id, Duration::hours(-1), Duration::hours(1)).await;
+
+        let (status, _) = contest_handlers::extend_contest(
+            State(state),
+            Extension(contestant),
+            Path(contest.id),
+            Json(ExtendContestRequest { new_end_time: contest.end_time + Duration::hours(1) }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        assert!(audit_entries(&pool).await.is_empty());
+    }
+
+    #[sqlx::test]
+    async fn failing_to_write_an_entry_does_not_panic(pool: PgPool) {
+        let (state, _queue) = test_state(pool.clone());
+
+        // An unknown actor violates the foreign key; the failure is only logged
+        audit(&state, "contest.create", Uuid::new_v4(), None, serde_json::json!({})).await;
+
+        assert!(audit_entries(&pool).await.is_empty());
+    }
+}
@@ -5,8 +5,10 @@ use axum::{
 };
 use shared::*;
 use serde::Deserialize;
+use serde_json::json;
 use uuid::Uuid;
 
+use super::audit;
 use crate::AppState;
 
 #[derive(Deserialize)]
@@ -57,5 +59,106 @@ pub async fn create_problem(
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
+    audit(&state, "problem.create", user.id, Some(problem.id), json!({"contest_id": problem.contest_id})).await;
+
     Ok(Json(problem))
-}
\ No newline at end of file
+}
+
+pub async fn get_comparison_config(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ComparisonConfig>, (StatusCode, Json<serde_json::Value>)> {
+    if !user.roles.contains(&"admin".to_string()) {
+        return Err((StatusCode::FORBIDDEN, Json(json!({"message":"Admin access required"}))));
+    }
+
+    let problem = state
+        .db
+        .get_problem(id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"message":"Database error"}))))?
+        .ok_or((StatusCode::NOT_FOUND, Json(json!({"message":"Problem not found"}))))?;
+
+    let config = ComparisonConfig::from_problem_metadata(&problem.metadata)
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"message":"Stored comparison config is malformed"}))))?;
+
+    Ok(Json(config))
+}
+
+pub async fn update_comparison_config(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ComparisonConfig>,
+) -> Result<Json<ComparisonConfig>, (StatusCode, Json<serde_json::Value>)> {
+    if !user.roles.contains(&"admin".to_string()) {
+        return Err((StatusCode::FORBIDDEN, Json(json!({"message":"Admin access required"}))));
+    }
+
+    payload
+        .validate()
+        .map_err(|message| (StatusCode::BAD_REQUEST, Json(json!({"message": message}))))?;
+
+    let problem = state
+        .db
+        .get_problem(id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"message":"Database error"}))))?
+        .ok_or((StatusCode::NOT_FOUND, Json(json!({"message":"Problem not found"}))))?;
+
+    // Keep the rest of the metadata intact; only the comparison key is replaced
+    let mut metadata = match problem.metadata {
+        serde_json::Value::Object(map) => map,
+        _ => serde_json::Map::new(),
+    };
+    let config_value = serde_json::to_value(&payload)
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"message":"Failed to encode config"}))))?;
+    metadata.insert(ComparisonConfig::METADATA_KEY.to_string(), config_value);
+
+    let problem = state
+        .db
+        .update_problem_metadata(id, &serde_json::Value::Object(metadata))
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"message":"Failed to update problem"}))))?;
+
+    audit(&state, "problem.comparison_config.update", user.id, Some(problem.id), json!({"contest_id": problem.contest_id, "config": payload})).await;
+
+    let config = ComparisonConfig::from_problem_metadata(&problem.metadata)
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"message":"Stored comparison config is malformed"}))))?;
+
+    Ok(Json(config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::*;
+    use sqlx::PgPool;
+
+    #[sqlx::test]
+    async fn comparison_config_put_is_reflected_on_next_read(pool: PgPool) {
+        let (state, _queue) = test_state(pool.clone());
+        let admin = insert_user(&state.db, "admin", &["admin"]).await;
+        let problem = insert_problem(&state.db, &pool, admin.id, None).await;
+
+        let Json(initial) = get_comparison_config(State(state.clone()), Extension(admin.clone()), Path(problem.id))
+            .await
+            .unwrap();
+        assert_eq!(initial, ComparisonConfig::default());
+
+        let config = ComparisonConfig {
+            mode: ComparisonMode::FloatingPoint { abs_tol: 1e-6, rel_tol: 1e-9 },
+            treat_pe_as_wa: true,
+        };
+        let Json(updated) = update_comparison_config(State(state.clone()), Extension(admin.clone()), Path(problem.id), Json(config.clone()))
+            .await
+            .unwrap();
+        assert_eq!(updated, config);
+
+        let Json(stored) = get_comparison_config(State(state), Extension(admin), Path(problem.id))
+            .await
+            .unwrap();
+        assert_eq!(stored, config);
+    }
+}
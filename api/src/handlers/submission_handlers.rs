@@ -14,7 +14,7 @@ pub async fn submit_code(
     Json(payload): Json<SubmissionRequest>,
 ) -> Result<Json<SubmissionResponse>, StatusCode> {
     // Verify problem exists
-    let _problem = state
+    let problem = state
         .db
         .get_problem(payload.problem_id)
         .await
@@ -36,6 +36,22 @@ pub async fn submit_code(
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
+    // Submissions to a running contest are judged on pretests only
+    let phase = match problem.contest_id {
+        Some(contest_id) => {
+            let contest = state
+                .db
+                .get_contest(contest_id)
+                .await
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+            match contest {
+                Some(contest) if chrono::Utc::now() < contest.end_time => JudgingPhase::Pretest,
+                _ => JudgingPhase::SystemTest,
+            }
+        }
+        None => JudgingPhase::SystemTest,
+    };
+
     // Create judging job
     let judging_job = JudgingJob {
         submission_id: submission.id,
@@ -43,6 +59,8 @@ pub async fn submit_code(
         problem_id: payload.problem_id,
         language_id: payload.language_id,
         source_code: payload.source_code,
+        phase,
+        dry_run: false,
     };
 
     // Send to queue
@@ -89,4 +107,254 @@ pub async fn get_problem_submissions(
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
     Ok(Json(submissions))
-}
\ No newline at end of file
+}
+
+/// Judge arbitrary source against a problem without creating a submission.
+/// The outcome is read back with [`get_dry_run`].
+pub async fn create_dry_run(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(problem_id): Path<Uuid>,
+    Json(payload): Json<DryRunRequest>,
+) -> Result<Json<DryRunResponse>, StatusCode> {
+    if !user.roles.contains(&"admin".to_string()) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let _problem = state
+        .db
+        .get_problem(problem_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let _language = state
+        .db
+        .get_language(payload.language_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::BAD_REQUEST)?;
+
+    let dry_run = state
+        .db
+        .create_dry_run(problem_id, &payload, user.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let judging_job = JudgingJob {
+        submission_id: dry_run.id,
+        user_id: user.id,
+        problem_id,
+        language_id: payload.language_id,
+        source_code: payload.source_code,
+        phase: JudgingPhase::SystemTest,
+        dry_run: true,
+    };
+
+    state
+        .queue
+        .publish_judging_job(&judging_job)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(DryRunResponse {
+        dry_run_id: dry_run.id,
+    }))
+}
+
+pub async fn get_dry_run(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<DryRun>, StatusCode> {
+    if !user.roles.contains(&"admin".to_string()) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let dry_run = state
+        .db
+        .get_dry_run(id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(dry_run))
+}
+
+const JUDGING_DURATION_SAMPLE_SIZE: i64 = 50;
+
+pub async fn get_queue_position(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<QueuePositionResponse>, StatusCode> {
+    let submission = state
+        .db
+        .get_submission(id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    if submission.user_id != user.id && !user.roles.contains(&"admin".to_string()) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    // Only queued submissions are waiting; anything else is already being
+    // judged or finished
+    if submission.status != JudgeStatus::Queued.as_db_str() {
+        return Ok(Json(QueuePositionResponse {
+            position: 0,
+            eta_seconds: Some(0.0),
+        }));
+    }
+
+    let position = state
+        .db
+        .count_queued_submissions_before(submission.submitted_at)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let average_duration = state
+        .db
+        .average_judging_duration_secs(JUDGING_DURATION_SAMPLE_SIZE)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(QueuePositionResponse {
+        position,
+        eta_seconds: average_duration
+            .map(|duration| estimate_queue_eta_secs(position, duration, state.config.judger_concurrency)),
+    }))
+}
+
+/// Seconds until a submission with `position` queued submissions ahead of it
+/// is judged, when `concurrency` jobs are judged at once and each takes
+/// `average_duration` seconds: the rounds needed to clear the submissions
+/// ahead, plus its own judging.
+fn estimate_queue_eta_secs(position: i64, average_duration: f64, concurrency: usize) -> f64 {
+    let concurrency = concurrency.max(1) as i64;
+    (position / concurrency + 1) as f64 * average_duration
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::*;
+    use sqlx::PgPool;
+
+    async fn count_rows(pool: &PgPool, table: &str) -> i64 {
+        let (count,): (i64,) = sqlx::query_as(&format!("SELECT COUNT(*) FROM {}", table))
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        count
+    }
+
+    #[test]
+    fn queue_eta_counts_rounds_of_concurrent_judging() {
+        let etas: Vec<f64> = (0..3).map(|position| estimate_queue_eta_secs(position, 10.0, 1)).collect();
+        assert_eq!(etas, vec![10.0, 20.0, 30.0]);
+
+        let etas: Vec<f64> = (0..3).map(|position| estimate_queue_eta_secs(position, 10.0, 2)).collect();
+        assert_eq!(etas, vec![10.0, 10.0, 20.0]);
+
+        assert_eq!(estimate_queue_eta_secs(2, 10.0, 0), 30.0);
+    }
+
+    #[sqlx::test]
+    async fn queue_position_of_three_queued_submissions_ignores_past_queue_wait(pool: PgPool) {
+        let (state, _queue) = test_state(pool.clone());
+        let admin = insert_user(&state.db, "admin", &["admin"]).await;
+        let contestant = insert_user(&state.db, "contestant", &["contestant"]).await;
+        let problem = insert_problem(&state.db, &pool, admin.id, None).await;
+
+        // A finished submission that waited 1000s in the queue but took 10s to judge
+        let finished = insert_submission(&state.db, &pool, contestant.id, problem.id).await;
+        sqlx::query(
+            r#"
+            UPDATE submissions
+            SET status = $1, submitted_at = NOW() - INTERVAL '1010 seconds',
+                judging_started_at = NOW() - INTERVAL '10 seconds', judged_at = NOW()
+            WHERE id = $2
+            "#,
+        )
+        .bind(JudgeStatus::Finished.as_db_str())
+        .bind(finished.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let mut queued = Vec::new();
+        for _ in 0..3 {
+            queued.push(insert_submission(&state.db, &pool, contestant.id, problem.id).await);
+        }
+
+        let mut positions = Vec::new();
+        let mut etas = Vec::new();
+        for submission in &queued {
+            let Json(response) = get_queue_position(State(state.clone()), Extension(contestant.clone()), Path(submission.id))
+                .await
+                .unwrap();
+            positions.push(response.position);
+            etas.push(response.eta_seconds.unwrap().round());
+        }
+
+        assert_eq!(positions, vec![0, 1, 2]);
+        assert_eq!(etas, vec![10.0, 20.0, 30.0]);
+    }
+
+    #[sqlx::test]
+    async fn dry_run_queues_a_dry_run_job_without_a_submission(pool: PgPool) {
+        let (state, queue) = test_state(pool.clone());
+        let admin = insert_user(&state.db, "admin", &["admin"]).await;
+        let problem = insert_problem(&state.db, &pool, admin.id, None).await;
+        let language_id = any_language_id(&pool).await;
+
+        let Json(response) = create_dry_run(
+            State(state.clone()),
+            Extension(admin.clone()),
+            Path(problem.id),
+            Json(DryRunRequest {
+                language_id,
+                source_code: "print(3)".to_string(),
+            }),
+        )
+        .await
+        .unwrap();
+
+        let jobs = queue.jobs.lock().unwrap().clone();
+        assert_eq!(jobs.len(), 1);
+        assert!(jobs[0].dry_run);
+        assert_eq!(jobs[0].submission_id, response.dry_run_id);
+        assert_eq!(count_rows(&pool, "submissions").await, 0);
+
+        let Json(dry_run) = get_dry_run(State(state), Extension(admin), Path(response.dry_run_id))
+            .await
+            .unwrap();
+        assert_eq!(dry_run.problem_id, problem.id);
+        assert_eq!(dry_run.status, JudgeStatus::Queued.as_db_str());
+        assert_eq!(dry_run.verdict, None);
+    }
+
+    #[sqlx::test]
+    async fn dry_run_requires_admin(pool: PgPool) {
+        let (state, queue) = test_state(pool.clone());
+        let admin = insert_user(&state.db, "admin", &["admin"]).await;
+        let contestant = insert_user(&state.db, "contestant", &["contestant"]).await;
+        let problem = insert_problem(&state.db, &pool, admin.id, None).await;
+
+        let status = create_dry_run(
+            State(state),
+            Extension(contestant),
+            Path(problem.id),
+            Json(DryRunRequest {
+                language_id: any_language_id(&pool).await,
+                source_code: String::new(),
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert!(queue.jobs.lock().unwrap().is_empty());
+    }
+}
@@ -5,6 +5,8 @@ mod database;
 mod queue;
 mod middleware;
 mod websocket;
+#[cfg(test)]
+mod test_support;
 
 use anyhow::Result;
 use axum::{
@@ -24,14 +26,14 @@ use crate::{
     config::Config,
     database::Database,
     handlers::*,
-    queue::Queue,
+    queue::{JobQueue, Queue},
     websocket::websocket_handler,
 };
 
 #[derive(Clone)]
 pub struct AppState {
     pub db: Database,
-    pub queue: Arc<Queue>,
+    pub queue: Arc<dyn JobQueue>,
     pub config: Arc<Config>,
 }
 
@@ -80,10 +82,18 @@ async fn main() -> Result<()> {
     .route("/api/profile/password", post(auth_handlers::change_password))
     .route("/api/auth/register-admin", post(auth_handlers::register_admin))
         .route("/api/problems", post(problem_handlers::create_problem))
+        .route("/api/problems/:id/comparison-config", get(problem_handlers::get_comparison_config).put(problem_handlers::update_comparison_config))
         .route("/api/submissions", post(submission_handlers::submit_code))
         .route("/api/submissions/:id", get(submission_handlers::get_submission))
+        .route("/api/submissions/:id/queue-position", get(submission_handlers::get_queue_position))
+        .route("/api/problems/:id/dry-runs", post(submission_handlers::create_dry_run))
+        .route("/api/dry-runs/:id", get(submission_handlers::get_dry_run))
         .route("/api/problems/:id/submissions", get(submission_handlers::get_problem_submissions))
         .route("/api/contests", post(contest_handlers::create_contest))
+        .route("/api/contests/:id/submission-histogram", get(contest_handlers::get_submission_histogram))
+        .route("/api/contests/:id/extend", axum::routing::put(contest_handlers::extend_contest))
+        .route("/api/contests/:id/system-test", post(contest_handlers::run_system_tests))
+        .route("/api/contests/:id/audit", get(contest_handlers::get_contest_audit_log))
         .route("/api/contest-admins", post(contest_admin_handlers::assign_contest_admin))
         .route("/api/contest-admins/:contest_id/:user_id", axum::routing::delete(contest_admin_handlers::remove_contest_admin))
         .route("/api/contests/:id/admins", get(contest_admin_handlers::list_contest_admins))
@@ -1,10 +1,17 @@
 use anyhow::Result;
+use futures_util::future::BoxFuture;
 use lapin::{
     options::*, publisher_confirm::Confirmation, types::FieldTable, BasicProperties, Connection,
     ConnectionProperties,
 };
 use shared::JudgingJob;
 
+/// Where handlers send judging jobs. [`Queue`] publishes to RabbitMQ; tests
+/// substitute an in-memory implementation.
+pub trait JobQueue: Send + Sync {
+    fn publish_judging_job<'a>(&'a self, job: &'a JudgingJob) -> BoxFuture<'a, Result<()>>;
+}
+
 pub struct Queue {
     connection: Connection,
 }
@@ -26,7 +33,7 @@ impl Queue {
         Ok(Queue { connection })
     }
 
-    pub async fn publish_judging_job(&self, job: &JudgingJob) -> Result<()> {
+    async fn publish(&self, job: &JudgingJob) -> Result<()> {
         let channel = self.connection.create_channel().await?;
         
         let payload = serde_json::to_vec(job)?;
@@ -51,4 +58,10 @@ impl Queue {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+impl JobQueue for Queue {
+    fn publish_judging_job<'a>(&'a self, job: &'a JudgingJob) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.publish(job))
+    }
+}
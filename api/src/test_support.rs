@@ -0,0 +1,121 @@
+use anyhow::Result;
+use futures_util::future::BoxFuture;
+use shared::*;
+use sqlx::PgPool;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use crate::{config::Config, database::Database, queue::JobQueue, AppState};
+
+/// Records published jobs instead of sending them to RabbitMQ.
+#[derive(Default)]
+pub struct RecordingQueue {
+    pub jobs: Mutex<Vec<JudgingJob>>,
+}
+
+impl JobQueue for RecordingQueue {
+    fn publish_judging_job<'a>(&'a self, job: &'a JudgingJob) -> BoxFuture<'a, Result<()>> {
+        self.jobs.lock().unwrap().push(job.clone());
+        Box::pin(async { Ok(()) })
+    }
+}
+
+pub fn test_config() -> Config {
+    Config {
+        server_address: "127.0.0.1:0".to_string(),
+        database_url: String::new(),
+        redis_url: String::new(),
+        rabbitmq_url: String::new(),
+        jwt_secret: "test-secret".to_string(),
+        max_contest_duration_secs: 14 * 24 * 60 * 60,
+        accepted_verdict_aliases: DEFAULT_ACCEPTED_VERDICT_ALIASES
+            .iter()
+            .map(|alias| alias.to_string())
+            .collect(),
+        judger_concurrency: 1,
+    }
+}
+
+pub fn test_state(pool: PgPool) -> (AppState, Arc<RecordingQueue>) {
+    let queue = Arc::new(RecordingQueue::default());
+    let state = AppState {
+        db: Database::from_pool(pool),
+        queue: queue.clone(),
+        config: Arc::new(test_config()),
+    };
+    (state, queue)
+}
+
+pub async fn insert_user(db: &Database, username: &str, roles: &[&str]) -> User {
+    db.create_user_with_roles(
+        username,
+        &format!("{}@example.com", username),
+        "not-a-real-hash",
+        roles.iter().map(|role| role.to_string()).collect(),
+    )
+    .await
+    .unwrap()
+}
+
+/// A contest running from `start_offset` to `end_offset` relative to now.
+pub async fn insert_contest(
+    db: &Database,
+    created_by: Uuid,
+    start_offset: chrono::Duration,
+    end_offset: chrono::Duration,
+) -> Contest {
+    let req = CreateContestRequest {
+        title: "Test contest".to_string(),
+        description: String::new(),
+        start_time: chrono::Utc::now() + start_offset,
+        duration: (end_offset - start_offset).num_seconds() as i32,
+    };
+    db.create_contest(&req, created_by).await.unwrap()
+}
+
+pub fn problem_request(question_type_id: Uuid, contest_id: Option<Uuid>) -> CreateProblemRequest {
+    CreateProblemRequest {
+        title: "A + B".to_string(),
+        statement: "Add two numbers.".to_string(),
+        difficulty: "easy".to_string(),
+        time_limit_ms: 1000,
+        memory_limit_kb: 65536,
+        question_type_id,
+        metadata: serde_json::json!({}),
+        points: 100,
+        contest_id,
+    }
+}
+
+pub async fn question_type_id(pool: &PgPool, name: &str) -> Uuid {
+    let (id,): (Uuid,) = sqlx::query_as("SELECT id FROM question_types WHERE name = $1")
+        .bind(name)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+    id
+}
+
+pub async fn insert_problem(db: &Database, pool: &PgPool, author_id: Uuid, contest_id: Option<Uuid>) -> Problem {
+    let question_type_id = question_type_id(pool, "ioi-standard").await;
+    db.create_problem(&problem_request(question_type_id, contest_id), author_id)
+        .await
+        .unwrap()
+}
+
+pub async fn any_language_id(pool: &PgPool) -> Uuid {
+    let (id,): (Uuid,) = sqlx::query_as("SELECT id FROM languages ORDER BY name LIMIT 1")
+        .fetch_one(pool)
+        .await
+        .unwrap();
+    id
+}
+
+pub async fn insert_submission(db: &Database, pool: &PgPool, user_id: Uuid, problem_id: Uuid) -> Submission {
+    let req = SubmissionRequest {
+        problem_id,
+        language_id: any_language_id(pool).await,
+        source_code: "print(1)".to_string(),
+    };
+    db.create_submission(&req, user_id).await.unwrap()
+}
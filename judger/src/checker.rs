@@ -0,0 +1,193 @@
+use shared::{ComparisonConfig, ComparisonMode, Verdict};
+
+/// Compare a program's output against the expected output according to the
+/// problem's comparison config.
+pub fn compare_output(expected: &str, actual: &str, config: &ComparisonConfig) -> Verdict {
+    match config.mode {
+        ComparisonMode::Exact => {
+            compare_with_presentation(expected == actual, expected, actual, config.treat_pe_as_wa)
+        }
+        ComparisonMode::Trimmed => compare_with_presentation(
+            expected.trim() == actual.trim(),
+            expected,
+            actual,
+            config.treat_pe_as_wa,
+        ),
+        ComparisonMode::IgnoreWhitespace => {
+            if tokens_match(expected, actual) {
+                Verdict::Accepted
+            } else {
+                Verdict::WrongAnswer
+            }
+        }
+        ComparisonMode::FloatingPoint { abs_tol, rel_tol } => {
+            compare_floats(expected, actual, abs_tol, rel_tol)
+        }
+    }
+}
+
+/// Output that fails the mode's strict check but has the same tokens as the
+/// expected output is a presentation error, unless configured to count as WA.
+fn compare_with_presentation(strict_match: bool, expected: &str, actual: &str, treat_pe_as_wa: bool) -> Verdict {
+    if strict_match {
+        Verdict::Accepted
+    } else if tokens_match(expected, actual) && !treat_pe_as_wa {
+        Verdict::PresentationError
+    } else {
+        Verdict::WrongAnswer
+    }
+}
+
+fn tokens_match(expected: &str, actual: &str) -> bool {
+    expected.split_whitespace().eq(actual.split_whitespace())
+}
+
+/// Token-wise comparison accepting a numeric token when it is within either
+/// `abs` of the expected value or `rel * |expected|`. Non-numeric tokens must
+/// match exactly, and a differing token count is a wrong answer.
+pub fn compare_floats(expected: &str, actual: &str, abs: f64, rel: f64) -> Verdict {
+    let expected_tokens: Vec<&str> = expected.split_whitespace().collect();
+    let actual_tokens: Vec<&str> = actual.split_whitespace().collect();
+
+    if expected_tokens.len() != actual_tokens.len() {
+        return Verdict::WrongAnswer;
+    }
+
+    let all_match = expected_tokens
+        .iter()
+        .zip(actual_tokens.iter())
+        .all(|(e, a)| match (e.parse::<f64>(), a.parse::<f64>()) {
+            (Ok(e), Ok(a)) => floats_close(e, a, abs, rel),
+            _ => e == a,
+        });
+
+    if all_match {
+        Verdict::Accepted
+    } else {
+        Verdict::WrongAnswer
+    }
+}
+
+fn floats_close(expected: f64, actual: f64, abs: f64, rel: f64) -> bool {
+    // NaN only matches NaN, and infinities must match exactly; otherwise the
+    // difference below would be NaN or infinite and never within tolerance
+    if expected.is_nan() {
+        return actual.is_nan();
+    }
+    if expected.is_infinite() {
+        return expected == actual;
+    }
+    if !actual.is_finite() {
+        return false;
+    }
+
+    // -0.0 and 0.0 differ by exactly zero, so they compare equal here
+    let diff = (expected - actual).abs();
+    diff <= abs || diff <= rel * expected.abs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn floats(expected: &str, actual: &str, abs: f64, rel: f64) -> Verdict {
+        compare_floats(expected, actual, abs, rel)
+    }
+
+    fn compare(mode: ComparisonMode, treat_pe_as_wa: bool, expected: &str, actual: &str) -> Verdict {
+        compare_output(expected, actual, &ComparisonConfig { mode, treat_pe_as_wa })
+    }
+
+    #[test]
+    fn exact_mode_distinguishes_ac_pe_and_wa() {
+        let expected = "1 2\n3\n";
+        assert_eq!(compare(ComparisonMode::Exact, false, expected, "1 2\n3\n"), Verdict::Accepted);
+        assert_eq!(compare(ComparisonMode::Exact, false, expected, "1  2\n3\n"), Verdict::PresentationError);
+        assert_eq!(compare(ComparisonMode::Exact, false, expected, "1 2\n4\n"), Verdict::WrongAnswer);
+    }
+
+    #[test]
+    fn exact_mode_treats_a_missing_trailing_newline_as_pe() {
+        assert_eq!(compare(ComparisonMode::Exact, false, "42\n", "42"), Verdict::PresentationError);
+        assert_eq!(compare(ComparisonMode::Exact, false, "42", "42\n"), Verdict::PresentationError);
+    }
+
+    #[test]
+    fn trimmed_mode_accepts_surrounding_whitespace_but_not_interior() {
+        let expected = "1 2\n3\n";
+        assert_eq!(compare(ComparisonMode::Trimmed, false, expected, "1 2\n3"), Verdict::Accepted);
+        assert_eq!(compare(ComparisonMode::Trimmed, false, expected, "\n1 2\n3\n\n"), Verdict::Accepted);
+        assert_eq!(compare(ComparisonMode::Trimmed, false, expected, "1\t2\n3\n"), Verdict::PresentationError);
+        assert_eq!(compare(ComparisonMode::Trimmed, false, expected, "1 2 3\n"), Verdict::PresentationError);
+        assert_eq!(compare(ComparisonMode::Trimmed, false, expected, "1 2\n"), Verdict::WrongAnswer);
+    }
+
+    #[test]
+    fn treat_pe_as_wa_turns_presentation_errors_into_wrong_answers() {
+        for mode in [ComparisonMode::Exact, ComparisonMode::Trimmed] {
+            assert_eq!(compare(mode, true, "1 2\n", "1 2\n"), Verdict::Accepted);
+            assert_eq!(compare(mode, false, "1 2\n", "1  2\n"), Verdict::PresentationError);
+            assert_eq!(compare(mode, true, "1 2\n", "1  2\n"), Verdict::WrongAnswer);
+            assert_eq!(compare(mode, true, "1 2\n", "1 3\n"), Verdict::WrongAnswer);
+        }
+    }
+
+    #[test]
+    fn ignore_whitespace_mode_never_reports_pe() {
+        assert_eq!(compare(ComparisonMode::IgnoreWhitespace, false, "1 2\n", "1\n\n2"), Verdict::Accepted);
+        assert_eq!(compare(ComparisonMode::IgnoreWhitespace, false, "1 2\n", "1 3"), Verdict::WrongAnswer);
+    }
+
+    #[test]
+    fn nan_only_matches_nan() {
+        assert_eq!(floats("NaN", "nan", 1.0, 1.0), Verdict::Accepted);
+        assert_eq!(floats("NaN", "0", 1.0, 1.0), Verdict::WrongAnswer);
+        assert_eq!(floats("0", "NaN", 1.0, 1.0), Verdict::WrongAnswer);
+    }
+
+    #[test]
+    fn infinities_must_match_exactly() {
+        assert_eq!(floats("inf", "inf", 0.0, 0.0), Verdict::Accepted);
+        assert_eq!(floats("-inf", "-inf", 0.0, 0.0), Verdict::Accepted);
+        assert_eq!(floats("inf", "-inf", 1.0, 1.0), Verdict::WrongAnswer);
+        assert_eq!(floats("1e308", "inf", 1.0, 1.0), Verdict::WrongAnswer);
+        assert_eq!(floats("inf", "1e308", 1.0, 1.0), Verdict::WrongAnswer);
+    }
+
+    #[test]
+    fn negative_zero_equals_zero() {
+        assert_eq!(floats("-0.0", "0.0", 0.0, 0.0), Verdict::Accepted);
+        assert_eq!(floats("0", "-0", 0.0, 0.0), Verdict::Accepted);
+    }
+
+    #[test]
+    fn absolute_tolerance_boundary() {
+        assert_eq!(floats("10", "10.5", 0.5, 0.0), Verdict::Accepted);
+        assert_eq!(floats("10", "9.5", 0.5, 0.0), Verdict::Accepted);
+        assert_eq!(floats("10", "10.75", 0.5, 0.0), Verdict::WrongAnswer);
+    }
+
+    #[test]
+    fn relative_tolerance_boundary_scales_with_expected_value() {
+        assert_eq!(floats("8", "10", 0.0, 0.25), Verdict::Accepted);
+        assert_eq!(floats("8", "6", 0.0, 0.25), Verdict::Accepted);
+        assert_eq!(floats("8", "10.5", 0.0, 0.25), Verdict::WrongAnswer);
+        // The same pair passes when the larger value is the expected one
+        assert_eq!(floats("10.5", "8", 0.0, 0.25), Verdict::Accepted);
+        assert_eq!(floats("0", "0.001", 0.0, 0.25), Verdict::WrongAnswer);
+    }
+
+    #[test]
+    fn non_numeric_tokens_must_match_exactly() {
+        assert_eq!(floats("yes 1.0", "yes 1.0001", 1e-3, 0.0), Verdict::Accepted);
+        assert_eq!(floats("yes 1.0", "Yes 1.0", 1e-3, 0.0), Verdict::WrongAnswer);
+        assert_eq!(floats("1.0", "one", 1e-3, 0.0), Verdict::WrongAnswer);
+    }
+
+    #[test]
+    fn token_count_mismatch_is_wrong_answer() {
+        assert_eq!(floats("1 2 3", "1 2", 1.0, 1.0), Verdict::WrongAnswer);
+        assert_eq!(floats("1 2", "1 2 3", 1.0, 1.0), Verdict::WrongAnswer);
+        assert_eq!(floats("1\n2", "1 2", 0.0, 0.0), Verdict::Accepted);
+    }
+}
@@ -7,6 +7,9 @@ pub struct Config {
     pub rabbitmq_url: String,
     pub work_dir: String,
     pub max_concurrent_jobs: usize,
+    pub judge_node_id: String,
+    /// Maximum number of compiled artifacts kept for reuse.
+    pub compile_cache_capacity: usize,
 }
 
 impl Config {
@@ -25,11 +28,21 @@ impl Config {
             .parse()
             .unwrap_or(4);
 
+        let judge_node_id = std::env::var("JUDGE_NODE_ID")
+            .unwrap_or_else(|_| format!("judger-{}", uuid::Uuid::new_v4()));
+
+        let compile_cache_capacity = std::env::var("COMPILE_CACHE_CAPACITY")
+            .unwrap_or_else(|_| "256".to_string())
+            .parse()
+            .unwrap_or(256);
+
         Ok(Config {
             database_url,
             rabbitmq_url,
             work_dir,
             max_concurrent_jobs,
+            judge_node_id,
+            compile_cache_capacity,
         })
     }
 }
\ No newline at end of file
@@ -1,18 +1,55 @@
 use anyhow::Result;
-use futures_util::StreamExt;
+use futures_util::{FutureExt, StreamExt};
 use lapin::{
     options::*, types::FieldTable, Connection, ConnectionProperties, Consumer,
 };
-use std::sync::Arc;
+use std::{future::Future, panic::AssertUnwindSafe, sync::Arc};
 use tokio::sync::Semaphore;
-use shared::*;
+use shared::{utils::safe_preview, *};
 
 use crate::{
+    checker::compare_output,
     config::Config,
     database::Database,
     executor::Executor,
 };
 
+/// Upper bound on the stdout/stderr stored with each test case result.
+const MAX_OUTPUT_PREVIEW_BYTES: usize = 64 * 1024;
+
+const COMPILE_CACHE_HIT_LOG: &str = "Compilation skipped: reused the artifact of an identical earlier compilation";
+
+/// What judging a submission produced.
+struct JudgeOutcome {
+    verdict: Verdict,
+    execution_time_ms: Option<i32>,
+    execution_memory_kb: Option<i32>,
+    test_results: Vec<TestCaseResult>,
+    /// Compiler diagnostics of a failed compilation.
+    diagnostics: Vec<CompilerDiagnostic>,
+    /// Raw compiler output of a failed compilation, or a note that an
+    /// earlier compilation was reused.
+    compile_log: Option<String>,
+}
+
+impl JudgeOutcome {
+    fn new(
+        verdict: Verdict,
+        execution_time_ms: Option<i32>,
+        execution_memory_kb: Option<i32>,
+        test_results: Vec<TestCaseResult>,
+    ) -> Self {
+        JudgeOutcome {
+            verdict,
+            execution_time_ms,
+            execution_memory_kb,
+            test_results,
+            diagnostics: Vec::new(),
+            compile_log: None,
+        }
+    }
+}
+
 pub struct Coordinator {
     config: Arc<Config>,
     db: Database,
@@ -61,17 +98,27 @@ impl Coordinator {
         while let Some(delivery) = consumer.next().await {
             match delivery {
                 Ok(delivery) => {
+                    let job: JudgingJob = match serde_json::from_slice(&delivery.data) {
+                        Ok(job) => job,
+                        Err(e) => {
+                            tracing::error!("Discarding malformed judging job: {}", e);
+                            if let Err(e) = delivery.ack(BasicAckOptions::default()).await {
+                                tracing::error!("Failed to ack message: {}", e);
+                            }
+                            continue;
+                        }
+                    };
+
                     let permit = self.semaphore.clone().acquire_owned().await?;
                     let db = self.db.clone();
                     let executor = self.executor.clone();
+                    let judge_node_id = self.config.judge_node_id.clone();
                     
                     tokio::spawn(async move {
                         let _permit = permit; // Hold permit until task completes
                         
-                        let data = delivery.data.clone();
-                        if let Err(e) = Self::process_job(data, db, executor).await {
-                            tracing::error!("Failed to process job: {}", e);
-                        }
+                        let process = Self::process_job(job.clone(), db.clone(), executor, judge_node_id);
+                        Self::run_guarded(&job, &db, process).await;
                         
                         if let Err(e) = delivery.ack(BasicAckOptions::default()).await {
                             tracing::error!("Failed to ack message: {}", e);
@@ -87,17 +134,68 @@ impl Coordinator {
         Ok(())
     }
 
-    async fn process_job(data: Vec<u8>, db: Database, executor: Executor) -> Result<()> {
-        let job: JudgingJob = serde_json::from_slice(&data)?;
-        tracing::info!("Processing submission {}", job.submission_id);
+    /// Run a job's processing future to completion. A panic while judging
+    /// must neither kill the consumer nor leave the delivery unacked (it would
+    /// be redelivered and panic again), so it is caught and recorded as a
+    /// system error on the submission.
+    async fn run_guarded<F>(job: &JudgingJob, db: &Database, process: F)
+    where
+        F: Future<Output = Result<()>>,
+    {
+        match AssertUnwindSafe(process).catch_unwind().await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => tracing::error!("Failed to process job: {}", e),
+            Err(panic) => {
+                let message = panic
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| panic.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "unknown panic".to_string());
+                tracing::error!("Processing submission {} panicked: {}", job.submission_id, message);
+
+                if let Err(e) = Self::record_system_error(db, job).await {
+                    tracing::error!("Failed to record system error for submission {}: {}", job.submission_id, e);
+                }
+            }
+        }
+    }
 
-        // Update status to compiling
-        db.update_submission_status(job.submission_id, "Compiling").await?;
+    async fn record_system_error(db: &Database, job: &JudgingJob) -> Result<()> {
+        if job.dry_run {
+            return db.update_dry_run_result(
+                job.submission_id,
+                JudgeStatus::Error.as_db_str(),
+                Some(Verdict::SystemError.as_db_str()),
+                None,
+                None,
+                &[],
+                None,
+            ).await;
+        }
+
+        db.update_submission_result(
+            job.submission_id,
+            JudgeStatus::Error.as_db_str(),
+            Some(Verdict::SystemError.as_db_str()),
+            None,
+            None,
+            None,
+        ).await
+    }
+
+    async fn process_job(job: JudgingJob, db: Database, executor: Executor, judge_node_id: String) -> Result<()> {
+        tracing::info!("Processing submission {} on node {}", job.submission_id, judge_node_id);
+
+        if !job.dry_run {
+            // Update status to compiling
+            db.update_submission_status(job.submission_id, JudgeStatus::Compiling.as_db_str()).await?;
+            db.mark_judging_started(job.submission_id, &judge_node_id).await?;
+
+            let _submission = db.get_submission(job.submission_id).await?
+                .ok_or_else(|| anyhow::anyhow!("Submission not found"))?;
+        }
 
         // Get required data
-        let _submission = db.get_submission(job.submission_id).await?
-            .ok_or_else(|| anyhow::anyhow!("Submission not found"))?;
-        
         let problem = db.get_problem(job.problem_id).await?
             .ok_or_else(|| anyhow::anyhow!("Problem not found"))?;
         
@@ -107,47 +205,89 @@ impl Coordinator {
         let question_type = db.get_question_type(problem.question_type_id).await?
             .ok_or_else(|| anyhow::anyhow!("Question type not found"))?;
         
-        let test_cases = db.get_test_cases(problem.id).await?;
+        let test_cases = select_test_cases(db.get_test_cases(problem.id).await?, job.phase);
+        if !job.dry_run {
+            db.update_submission_phase(job.submission_id, job.phase.as_db_str()).await?;
+        }
 
-        // Process based on question type
-        let result = match question_type.name.as_str() {
-            "ioi-standard" => {
-                Self::judge_ioi_standard(&executor, &job, &problem, &language, &test_cases).await
-            }
-            "output-only" => {
-                Self::judge_output_only(&job, &test_cases).await
-            }
-            "interactive" => {
-                Self::judge_interactive(&executor, &job, &problem, &language, &test_cases).await
+        // Process based on question type, rejecting disallowed languages
+        // before any compilation is attempted. The rejection is explained in
+        // the judge log so it is not mistaken for a real compile failure.
+        let judge_log = (!problem.allows_language(&language))
+            .then(|| problem.disallowed_language_message(&language));
+        let result = if judge_log.is_some() {
+            tracing::info!(
+                "Submission {} rejected: language {} is not allowed for problem {}",
+                job.submission_id,
+                language.name,
+                problem.id
+            );
+            Ok(JudgeOutcome::new(Verdict::CompilationError, None, None, vec![]))
+        } else {
+            match question_type.name.as_str() {
+                "ioi-standard" => {
+                    Self::judge_ioi_standard(&executor, &job, &problem, &language, &test_cases).await
+                }
+                "output-only" => {
+                    Self::judge_output_only(&job, &problem, &test_cases).await
+                }
+                "interactive" => {
+                    Self::judge_interactive(&executor, &job, &problem, &language, &test_cases).await
+                }
+                _ => Err(anyhow::anyhow!("Unknown question type: {}", question_type.name))
             }
-            _ => Err(anyhow::anyhow!("Unknown question type: {}", question_type.name))
         };
 
+        // Dry runs report their outcome on the dry run; submissions are never
+        // touched
+        if job.dry_run {
+            match result {
+                Ok(outcome) => {
+                    db.update_dry_run_result(
+                        job.submission_id,
+                        JudgeStatus::Finished.as_db_str(),
+                        Some(outcome.verdict.as_db_str()),
+                        outcome.execution_time_ms,
+                        outcome.execution_memory_kb,
+                        &outcome.test_results,
+                        judge_log.as_deref(),
+                    ).await?;
+                }
+                Err(e) => {
+                    tracing::error!("Dry run {} failed on node {}: {}", job.submission_id, judge_node_id, e);
+                    Self::record_system_error(&db, &job).await?;
+                }
+            }
+            return Ok(());
+        }
+
         match result {
-            Ok((verdict, execution_time, execution_memory, test_results)) => {
+            Ok(outcome) => {
                 // Update submission with final result
                 db.update_submission_result(
                     job.submission_id,
-                    "Finished",
-                    Some(&format!("{:?}", verdict)),
-                    execution_time,
-                    execution_memory,
+                    JudgeStatus::Finished.as_db_str(),
+                    Some(outcome.verdict.as_db_str()),
+                    outcome.execution_time_ms,
+                    outcome.execution_memory_kb,
+                    judge_log.as_deref(),
+                ).await?;
+                db.update_submission_compile_output(
+                    job.submission_id,
+                    &outcome.diagnostics,
+                    outcome.compile_log.as_deref(),
                 ).await?;
 
-                // Store individual test case results
-                for result in test_results {
+                // Store individual test case results, replacing those of an
+                // earlier phase when a submission is re-judged
+                db.delete_submission_results(job.submission_id).await?;
+                for result in outcome.test_results {
                     db.create_submission_result(&result, job.submission_id).await?;
                 }
             }
             Err(e) => {
-                tracing::error!("Judging failed: {}", e);
-                db.update_submission_result(
-                    job.submission_id,
-                    "Error",
-                    Some("SystemError"),
-                    None,
-                    None,
-                ).await?;
+                tracing::error!("Judging failed on node {}: {}", judge_node_id, e);
+                Self::record_system_error(&db, &job).await?;
             }
         }
 
@@ -160,16 +300,27 @@ impl Coordinator {
         problem: &Problem,
         language: &Language,
         test_cases: &[TestCase],
-    ) -> Result<(Verdict, Option<i32>, Option<i32>, Vec<TestCaseResult>)> {
+    ) -> Result<JudgeOutcome> {
         // Compile the code
         let compile_result = executor.compile(&job.source_code, language).await?;
+        let compile_log = compile_result.cache_hit.then(|| {
+            tracing::info!("Submission {} reused a cached compilation", job.submission_id);
+            COMPILE_CACHE_HIT_LOG.to_string()
+        });
         if !compile_result.success {
-            return Ok((Verdict::CompilationError, None, None, vec![]));
+            return Ok(JudgeOutcome {
+                diagnostics: compile_result.diagnostics,
+                compile_log: compile_result.error_message,
+                ..JudgeOutcome::new(Verdict::CompilationError, None, None, vec![])
+            });
         }
 
+        let comparison = comparison_config(problem)?;
+
         let mut results = Vec::new();
         let mut total_time = 0;
         let mut max_memory = 0;
+        let mut final_verdict = Verdict::Accepted;
 
         // Run against each test case
         for test_case in test_cases {
@@ -186,10 +337,8 @@ impl Coordinator {
                 Verdict::TimeLimitExceeded
             } else if run_result.memory_kb > problem.memory_limit_kb {
                 Verdict::MemoryLimitExceeded
-            } else if run_result.stdout.trim() == test_case.output_data.trim() {
-                Verdict::Accepted
             } else {
-                Verdict::WrongAnswer
+                compare_output(&test_case.output_data, &run_result.stdout, &comparison)
             };
 
             total_time += run_result.time_ms;
@@ -200,54 +349,49 @@ impl Coordinator {
                 verdict: verdict.clone(),
                 execution_time_ms: Some(run_result.time_ms),
                 execution_memory_kb: Some(run_result.memory_kb),
-                stdout: Some(run_result.stdout),
-                stderr: Some(run_result.stderr),
+                stdout: Some(safe_preview(run_result.stdout.as_bytes(), MAX_OUTPUT_PREVIEW_BYTES)),
+                stderr: Some(safe_preview(run_result.stderr.as_bytes(), MAX_OUTPUT_PREVIEW_BYTES)),
             };
 
             results.push(test_result);
 
-            // If any test case fails, return early
+            // If any test case fails, stop early
             if !matches!(verdict, Verdict::Accepted) {
-                return Ok((verdict, Some(total_time), Some(max_memory), results));
+                final_verdict = verdict;
+                break;
             }
         }
 
-        Ok((Verdict::Accepted, Some(total_time), Some(max_memory), results))
+        Ok(JudgeOutcome {
+            compile_log,
+            ..JudgeOutcome::new(final_verdict, Some(total_time), Some(max_memory), results)
+        })
     }
 
     async fn judge_output_only(
         job: &JudgingJob,
+        problem: &Problem,
         test_cases: &[TestCase],
-    ) -> Result<(Verdict, Option<i32>, Option<i32>, Vec<TestCaseResult>)> {
-        // For output-only problems, the source code is the answer
-        let submitted_output = job.source_code.trim();
-        
-        let mut results = Vec::new();
-        
-        // Usually there's only one test case for output-only problems
-        for test_case in test_cases {
-            let verdict = if submitted_output == test_case.output_data.trim() {
-                Verdict::Accepted
-            } else {
-                Verdict::WrongAnswer
-            };
-
-            let test_result = TestCaseResult {
-                test_case_id: test_case.id,
-                verdict: verdict.clone(),
-                execution_time_ms: Some(0),
-                execution_memory_kb: Some(0),
-                stdout: Some(submitted_output.to_string()),
-                stderr: None,
-            };
+    ) -> Result<JudgeOutcome> {
+        // For output-only problems, the source code is the answer, checked
+        // against the first test case (there is usually only one)
+        let Some(test_case) = test_cases.first() else {
+            return Ok(JudgeOutcome::new(Verdict::WrongAnswer, Some(0), Some(0), vec![]));
+        };
 
-            results.push(test_result);
+        let comparison = comparison_config(problem)?;
+        let verdict = compare_output(&test_case.output_data, &job.source_code, &comparison);
 
-            // Return after first test case (output-only usually has one)
-            return Ok((verdict, Some(0), Some(0), results));
-        }
+        let test_result = TestCaseResult {
+            test_case_id: test_case.id,
+            verdict: verdict.clone(),
+            execution_time_ms: Some(0),
+            execution_memory_kb: Some(0),
+            stdout: Some(safe_preview(job.source_code.as_bytes(), MAX_OUTPUT_PREVIEW_BYTES)),
+            stderr: None,
+        };
 
-        Ok((Verdict::WrongAnswer, Some(0), Some(0), results))
+        Ok(JudgeOutcome::new(verdict, Some(0), Some(0), vec![test_result]))
     }
 
     async fn judge_interactive(
@@ -256,12 +400,275 @@ impl Coordinator {
         _problem: &Problem,
         _language: &Language,
         _test_cases: &[TestCase],
-    ) -> Result<(Verdict, Option<i32>, Option<i32>, Vec<TestCaseResult>)> {
+    ) -> Result<JudgeOutcome> {
         // Interactive problems require more complex setup with interactor programs
         // This is a simplified placeholder - full implementation would require
         // running both the user's program and the interactor with proper IPC
         
         // For now, return system error as this needs more implementation
-        Ok((Verdict::SystemError, None, None, vec![]))
+        Ok(JudgeOutcome::new(Verdict::SystemError, None, None, vec![]))
+    }
+}
+
+/// The test cases judged in `phase`. Pretest judging runs only the pretest
+/// subset; problems without any flagged pretests are judged on the full set.
+fn select_test_cases(mut test_cases: Vec<TestCase>, phase: JudgingPhase) -> Vec<TestCase> {
+    if phase == JudgingPhase::Pretest && test_cases.iter().any(|tc| tc.is_pretest) {
+        test_cases.retain(|tc| tc.is_pretest);
+    }
+    test_cases
+}
+
+/// The problem's comparison config. A config that is present but malformed
+/// fails the job rather than silently judging with the default mode.
+fn comparison_config(problem: &Problem) -> Result<ComparisonConfig> {
+    ComparisonConfig::from_problem_metadata(&problem.metadata)
+        .map_err(|e| anyhow::anyhow!("Invalid comparison config for problem {}: {}", problem.id, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::*;
+    use sqlx::PgPool;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use uuid::Uuid;
+
+    fn test_case(order_index: i32, is_pretest: bool) -> TestCase {
+        TestCase {
+            id: Uuid::new_v4(),
+            problem_id: Uuid::nil(),
+            input_data: String::new(),
+            output_data: String::new(),
+            is_sample: false,
+            is_pretest,
+            order_index,
+        }
+    }
+
+    fn order(test_cases: &[TestCase]) -> Vec<i32> {
+        test_cases.iter().map(|tc| tc.order_index).collect()
+    }
+
+    #[test]
+    fn pretest_phase_runs_only_flagged_tests_and_system_test_runs_all() {
+        let test_cases = vec![test_case(0, true), test_case(1, false), test_case(2, true), test_case(3, false)];
+
+        let pretests = select_test_cases(test_cases.clone(), JudgingPhase::Pretest);
+        assert_eq!(order(&pretests), vec![0, 2]);
+
+        let system_tests = select_test_cases(test_cases, JudgingPhase::SystemTest);
+        assert_eq!(order(&system_tests), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn pretest_phase_without_flagged_tests_runs_all() {
+        let test_cases = vec![test_case(0, false), test_case(1, false)];
+
+        let selected = select_test_cases(test_cases, JudgingPhase::Pretest);
+        assert_eq!(order(&selected), vec![0, 1]);
+    }
+
+    #[sqlx::test(migrations = "../api/migrations")]
+    async fn panicking_job_is_recorded_and_does_not_block_the_next_job(pool: PgPool) {
+        let db = Database::from_pool(pool.clone());
+        let executor = test_executor();
+        let semaphore = Arc::new(Semaphore::new(1));
+
+        let panicking_job = insert_submission(&pool, "output-only", "42", "42").await;
+        let next_job = insert_submission(&pool, "output-only", "42", "42").await;
+
+        let permit = semaphore.clone().acquire_owned().await.unwrap();
+        let first = tokio::spawn({
+            let db = db.clone();
+            let job = panicking_job.clone();
+            async move {
+                let _permit = permit;
+                Coordinator::run_guarded(&job, &db, async { panic!("checker exploded") }).await;
+            }
+        });
+
+        let next_ran = Arc::new(AtomicBool::new(false));
+        let permit = semaphore.clone().acquire_owned().await.unwrap();
+        let second = tokio::spawn({
+            let db = db.clone();
+            let job = next_job.clone();
+            let next_ran = next_ran.clone();
+            async move {
+                let _permit = permit;
+                let process = Coordinator::process_job(job.clone(), db.clone(), executor, "test-node".to_string());
+                Coordinator::run_guarded(&job, &db, async {
+                    next_ran.store(true, Ordering::SeqCst);
+                    process.await
+                })
+                .await;
+            }
+        });
+
+        first.await.unwrap();
+        second.await.unwrap();
+        assert!(next_ran.load(Ordering::SeqCst));
+
+        let panicked = db.get_submission(panicking_job.submission_id).await.unwrap().unwrap();
+        assert_eq!(panicked.status, JudgeStatus::Error.as_db_str());
+        assert_eq!(panicked.verdict.as_deref(), Some(Verdict::SystemError.as_db_str()));
+
+        let judged = db.get_submission(next_job.submission_id).await.unwrap().unwrap();
+        assert_eq!(judged.status, JudgeStatus::Finished.as_db_str());
+        assert_eq!(judged.verdict.as_deref(), Some(Verdict::Accepted.as_db_str()));
     }
-}
\ No newline at end of file
+
+    async fn count_rows(pool: &PgPool, table: &str) -> i64 {
+        let (count,): (i64,) = sqlx::query_as(&format!("SELECT COUNT(*) FROM {}", table))
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        count
+    }
+
+    #[sqlx::test(migrations = "../api/migrations")]
+    async fn dry_run_reports_its_verdict_without_writing_submission_rows(pool: PgPool) {
+        let db = Database::from_pool(pool.clone());
+        let job = insert_dry_run(&pool, "output-only", "42", "41").await;
+
+        Coordinator::process_job(job.clone(), db, test_executor(), "test-node".to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(count_rows(&pool, "submissions").await, 0);
+        assert_eq!(count_rows(&pool, "submission_results").await, 0);
+
+        let (status, verdict, test_results): (String, Option<String>, serde_json::Value) =
+            sqlx::query_as("SELECT status, verdict, test_results FROM dry_runs WHERE id = $1")
+                .bind(job.submission_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(status, JudgeStatus::Finished.as_db_str());
+        assert_eq!(verdict.as_deref(), Some(Verdict::WrongAnswer.as_db_str()));
+        assert_eq!(test_results.as_array().map(Vec::len), Some(1));
+    }
+
+    #[sqlx::test(migrations = "../api/migrations")]
+    async fn disallowed_language_is_rejected_with_a_judge_log(pool: PgPool) {
+        let db = Database::from_pool(pool.clone());
+        let job = insert_submission(&pool, "output-only", "42", "42").await;
+        sqlx::query("UPDATE problems SET metadata = $1 WHERE id = $2")
+            .bind(serde_json::json!({"allowed_languages": ["C++17"]}))
+            .bind(job.problem_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        Coordinator::process_job(job.clone(), db.clone(), test_executor(), "test-node".to_string())
+            .await
+            .unwrap();
+
+        let submission = db.get_submission(job.submission_id).await.unwrap().unwrap();
+        assert_eq!(submission.verdict.as_deref(), Some(Verdict::CompilationError.as_db_str()));
+        assert_eq!(
+            submission.judge_log.as_deref(),
+            Some("Python 3 is not allowed for this problem. Allowed languages: C++17")
+        );
+    }
+
+    #[sqlx::test(migrations = "../api/migrations")]
+    async fn judged_submission_records_its_node_and_start_time(pool: PgPool) {
+        let db = Database::from_pool(pool.clone());
+        let job = insert_submission(&pool, "output-only", "42", "42").await;
+
+        Coordinator::process_job(job.clone(), db.clone(), test_executor(), "test-node".to_string())
+            .await
+            .unwrap();
+
+        let submission = db.get_submission(job.submission_id).await.unwrap().unwrap();
+        assert_eq!(submission.verdict.as_deref(), Some(Verdict::Accepted.as_db_str()));
+        assert_eq!(submission.judge_node_id.as_deref(), Some("test-node"));
+        let started_at = submission.judging_started_at.unwrap();
+        assert!(started_at <= submission.judged_at.unwrap());
+    }
+
+    /// Submit `source` as a shell script that plays a failing C compiler.
+    async fn insert_failing_compilation(pool: &PgPool, source: &str) -> JudgingJob {
+        let job = insert_submission(pool, "ioi-standard", "42", source).await;
+        sqlx::query("UPDATE languages SET compile_command = 'sh solution.c', file_extension = 'c' WHERE id = $1")
+            .bind(job.language_id)
+            .execute(pool)
+            .await
+            .unwrap();
+        job
+    }
+
+    #[sqlx::test(migrations = "../api/migrations")]
+    async fn compile_diagnostics_are_persisted(pool: PgPool) {
+        let db = Database::from_pool(pool.clone());
+        let source = "echo \"solution.c:3:7: error: expected ';' before '}' token\" >&2; exit 1";
+        let job = insert_failing_compilation(&pool, source).await;
+
+        Coordinator::process_job(job.clone(), db.clone(), test_executor(), "test-node".to_string())
+            .await
+            .unwrap();
+
+        let submission = db.get_submission(job.submission_id).await.unwrap().unwrap();
+        assert_eq!(submission.verdict.as_deref(), Some(Verdict::CompilationError.as_db_str()));
+        assert_eq!(
+            submission.compile_diagnostics,
+            serde_json::json!([{
+                "severity": "Error",
+                "line": 3,
+                "column": 7,
+                "message": "expected ';' before '}' token",
+            }])
+        );
+        assert_eq!(
+            submission.compile_log.as_deref(),
+            Some("solution.c:3:7: error: expected ';' before '}' token\n")
+        );
+    }
+
+    #[sqlx::test(migrations = "../api/migrations")]
+    async fn rejudge_records_a_compile_cache_hit(pool: PgPool) {
+        let db = Database::from_pool(pool.clone());
+        let job = insert_submission(&pool, "ioi-standard", "42", "#!/bin/sh\necho 42\n").await;
+        // "Compiling" installs the script itself as the executable
+        sqlx::query("UPDATE languages SET compile_command = 'install -m 755 solution.c solution', file_extension = 'c' WHERE id = $1")
+            .bind(job.language_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let executor = test_executor();
+
+        Coordinator::process_job(job.clone(), db.clone(), executor.clone(), "test-node".to_string())
+            .await
+            .unwrap();
+        let first = db.get_submission(job.submission_id).await.unwrap().unwrap();
+        assert_eq!(first.verdict.as_deref(), Some(Verdict::Accepted.as_db_str()));
+        assert_eq!(first.compile_log, None);
+
+        Coordinator::process_job(job.clone(), db.clone(), executor, "test-node".to_string())
+            .await
+            .unwrap();
+        let rejudged = db.get_submission(job.submission_id).await.unwrap().unwrap();
+        assert_eq!(rejudged.verdict.as_deref(), Some(Verdict::Accepted.as_db_str()));
+        assert_eq!(rejudged.compile_log.as_deref(), Some(COMPILE_CACHE_HIT_LOG));
+    }
+
+    #[sqlx::test(migrations = "../api/migrations")]
+    async fn unparseable_compile_log_is_kept_raw(pool: PgPool) {
+        let db = Database::from_pool(pool.clone());
+        let source = "echo \"/usr/bin/ld: main.o: undefined reference to 'solve'\" >&2; exit 1";
+        let job = insert_failing_compilation(&pool, source).await;
+
+        Coordinator::process_job(job.clone(), db.clone(), test_executor(), "test-node".to_string())
+            .await
+            .unwrap();
+
+        let submission = db.get_submission(job.submission_id).await.unwrap().unwrap();
+        assert_eq!(submission.verdict.as_deref(), Some(Verdict::CompilationError.as_db_str()));
+        assert_eq!(submission.compile_diagnostics, serde_json::json!([]));
+        assert_eq!(
+            submission.compile_log.as_deref(),
+            Some("/usr/bin/ld: main.o: undefined reference to 'solve'\n")
+        );
+    }
+}
@@ -14,6 +14,11 @@ impl Database {
         Ok(Database { pool })
     }
 
+    #[cfg(test)]
+    pub fn from_pool(pool: PgPool) -> Self {
+        Database { pool }
+    }
+
     pub async fn get_submission(&self, id: Uuid) -> Result<Option<Submission>> {
         let submission = sqlx::query_as::<_, Submission>(
             "SELECT * FROM submissions WHERE id = $1"
@@ -81,6 +86,31 @@ impl Database {
         Ok(())
     }
 
+    pub async fn update_submission_phase(&self, id: Uuid, phase: &str) -> Result<()> {
+        sqlx::query(
+            "UPDATE submissions SET judging_phase = $1 WHERE id = $2"
+        )
+        .bind(phase)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Record the node that picked the submission up and when judging began.
+    pub async fn mark_judging_started(&self, id: Uuid, judge_node_id: &str) -> Result<()> {
+        sqlx::query(
+            "UPDATE submissions SET judge_node_id = $1, judging_started_at = NOW() WHERE id = $2"
+        )
+        .bind(judge_node_id)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn update_submission_result(
         &self,
         id: Uuid,
@@ -88,18 +118,41 @@ impl Database {
         verdict: Option<&str>,
         execution_time_ms: Option<i32>,
         execution_memory_kb: Option<i32>,
+        judge_log: Option<&str>,
     ) -> Result<()> {
         sqlx::query(
             r#"
             UPDATE submissions 
-            SET status = $1, verdict = $2, execution_time_ms = $3, execution_memory_kb = $4 
-            WHERE id = $5
+            SET status = $1, verdict = $2, execution_time_ms = $3, execution_memory_kb = $4,
+                judge_log = $5, judged_at = NOW()
+            WHERE id = $6
             "#
         )
         .bind(status)
         .bind(verdict)
         .bind(execution_time_ms)
         .bind(execution_memory_kb)
+        .bind(judge_log)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Store the compiler diagnostics and raw compiler log of the latest
+    /// judging, replacing any from an earlier one.
+    pub async fn update_submission_compile_output(
+        &self,
+        id: Uuid,
+        diagnostics: &[CompilerDiagnostic],
+        compile_log: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE submissions SET compile_diagnostics = $1, compile_log = $2 WHERE id = $3"
+        )
+        .bind(serde_json::to_value(diagnostics)?)
+        .bind(compile_log)
         .bind(id)
         .execute(&self.pool)
         .await?;
@@ -107,6 +160,49 @@ impl Database {
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
+    pub async fn update_dry_run_result(
+        &self,
+        id: Uuid,
+        status: &str,
+        verdict: Option<&str>,
+        execution_time_ms: Option<i32>,
+        execution_memory_kb: Option<i32>,
+        test_results: &[TestCaseResult],
+        judge_log: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE dry_runs
+            SET status = $1, verdict = $2, execution_time_ms = $3, execution_memory_kb = $4,
+                test_results = $5, judge_log = $6, judged_at = NOW()
+            WHERE id = $7
+            "#
+        )
+        .bind(status)
+        .bind(verdict)
+        .bind(execution_time_ms)
+        .bind(execution_memory_kb)
+        .bind(serde_json::to_value(test_results)?)
+        .bind(judge_log)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_submission_results(&self, submission_id: Uuid) -> Result<()> {
+        sqlx::query(
+            "DELETE FROM submission_results WHERE submission_id = $1"
+        )
+        .bind(submission_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn create_submission_result(&self, result: &TestCaseResult, submission_id: Uuid) -> Result<()> {
         sqlx::query(
             r#"
@@ -118,7 +214,7 @@ impl Database {
         .bind(Uuid::new_v4())
         .bind(submission_id)
         .bind(result.test_case_id)
-        .bind(format!("{:?}", result.verdict))
+        .bind(result.verdict.as_db_str())
         .bind(result.execution_time_ms)
         .bind(result.execution_memory_kb)
         .bind(&result.stdout)
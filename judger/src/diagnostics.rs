@@ -0,0 +1,195 @@
+use shared::{CompilerDiagnostic, DiagnosticSeverity, Language};
+
+/// Extract diagnostics from a compiler log, choosing the parser from the
+/// language's file extension. Languages without a parser yield no
+/// diagnostics; the raw log is always kept separately.
+pub fn parse_diagnostics(language: &Language, log: &str) -> Vec<CompilerDiagnostic> {
+    match language.file_extension.as_str() {
+        "c" | "cc" | "cpp" => parse_gcc(log),
+        "java" => parse_javac(log),
+        _ => Vec::new(),
+    }
+}
+
+/// Parse gcc/g++ output of the form `file:line:col: severity: message`.
+/// `note:` entries and indented continuation text are attached to the
+/// preceding diagnostic; the echoed source, caret and fix-it lines are not.
+pub fn parse_gcc(log: &str) -> Vec<CompilerDiagnostic> {
+    let mut diagnostics: Vec<CompilerDiagnostic> = Vec::new();
+
+    for line in log.lines() {
+        if let Some(diagnostic) = parse_header(line) {
+            if diagnostic.severity == DiagnosticSeverity::Note {
+                if let Some(primary) = diagnostics.last_mut() {
+                    append_line(primary, line.trim());
+                    continue;
+                }
+            }
+            diagnostics.push(diagnostic);
+        } else if line.starts_with(char::is_whitespace) && !line.trim().is_empty() && !is_gcc_source_echo(line) {
+            if let Some(primary) = diagnostics.last_mut() {
+                append_line(primary, line.trim());
+            }
+        }
+        // Anything else ("In function ...", "compilation terminated.") is
+        // context that is not tied to a location
+    }
+
+    diagnostics
+}
+
+/// Parse javac output of the form `File.java:line: error: message`, taking
+/// the column from the caret line that follows the echoed source line.
+pub fn parse_javac(log: &str) -> Vec<CompilerDiagnostic> {
+    let mut diagnostics: Vec<CompilerDiagnostic> = Vec::new();
+
+    for line in log.lines() {
+        if let Some(diagnostic) = parse_header(line) {
+            diagnostics.push(diagnostic);
+            continue;
+        }
+
+        let Some(primary) = diagnostics.last_mut() else {
+            continue;
+        };
+
+        if line.trim() == "^" {
+            if primary.column.is_none() {
+                primary.column = line.find('^').map(|i| i as u32 + 1);
+            }
+        } else if is_javac_detail(line) {
+            append_line(primary, line.trim());
+        }
+        // The echoed source line and the trailing "N errors" summary are skipped
+    }
+
+    diagnostics
+}
+
+/// gcc echoes source behind a `|` gutter holding the line number, nothing
+/// (caret lines) or `+++` (fix-it insertions), e.g. `    5 |     foo();`.
+fn is_gcc_source_echo(line: &str) -> bool {
+    line.split_once('|').is_some_and(|(gutter, _)| {
+        let gutter = gutter.trim();
+        gutter.is_empty() || gutter == "+++" || gutter.bytes().all(|b| b.is_ascii_digit())
+    })
+}
+
+/// javac indents both the echoed source line and the `symbol:`/`location:`
+/// details; only the latter belong to the diagnostic.
+fn is_javac_detail(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.len() != line.len() && (trimmed.starts_with("symbol:") || trimmed.starts_with("location:"))
+}
+
+fn parse_header(line: &str) -> Option<CompilerDiagnostic> {
+    let (_file, rest) = line.split_once(':')?;
+    let (line_no, rest) = rest.split_once(':')?;
+    let line_no: u32 = line_no.trim().parse().ok()?;
+
+    let (column, rest) = match rest.split_once(':') {
+        Some((column, remainder)) => match column.trim().parse::<u32>() {
+            Ok(column) => (Some(column), remainder),
+            Err(_) => (None, rest),
+        },
+        None => (None, rest),
+    };
+
+    let (severity, message) = rest.trim_start().split_once(':')?;
+    let severity = match severity.trim() {
+        "error" | "fatal error" => DiagnosticSeverity::Error,
+        "warning" => DiagnosticSeverity::Warning,
+        "note" => DiagnosticSeverity::Note,
+        _ => return None,
+    };
+
+    Some(CompilerDiagnostic {
+        severity,
+        line: line_no,
+        column,
+        message: message.trim().to_string(),
+    })
+}
+
+fn append_line(diagnostic: &mut CompilerDiagnostic, line: &str) {
+    diagnostic.message.push('\n');
+    diagnostic.message.push_str(line);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcc_notes_attach_to_the_primary_diagnostic_without_echoed_source() {
+        let log = "\
+solution.cpp: In function 'int main()':
+solution.cpp:5:5: error: 'foo' was not declared in this scope
+    5 |     foo();
+      |     ^~~
+solution.cpp:2:6: note: suggested alternative: 'for'
+    2 | void for_each();
+      |      ^~~~~~~~
+  +++ |+#include <cstdio>
+  candidate expects 1 argument, 0 provided
+solution.cpp:7:9: warning: unused variable 'x' [-Wunused-variable]
+compilation terminated.
+";
+        let diagnostics = parse_gcc(log);
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                CompilerDiagnostic {
+                    severity: DiagnosticSeverity::Error,
+                    line: 5,
+                    column: Some(5),
+                    message: "'foo' was not declared in this scope\n\
+                              solution.cpp:2:6: note: suggested alternative: 'for'\n\
+                              candidate expects 1 argument, 0 provided"
+                        .to_string(),
+                },
+                CompilerDiagnostic {
+                    severity: DiagnosticSeverity::Warning,
+                    line: 7,
+                    column: Some(9),
+                    message: "unused variable 'x' [-Wunused-variable]".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn javac_keeps_symbol_and_location_but_not_the_echoed_source() {
+        let log = "\
+Solution.java:4: error: cannot find symbol
+        int x = y;
+                ^
+  symbol:   variable y
+  location: class Solution
+Solution.java:6: error: ';' expected
+        return 0
+                ^
+2 errors
+";
+        let diagnostics = parse_javac(log);
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                CompilerDiagnostic {
+                    severity: DiagnosticSeverity::Error,
+                    line: 4,
+                    column: Some(17),
+                    message: "cannot find symbol\nsymbol:   variable y\nlocation: class Solution".to_string(),
+                },
+                CompilerDiagnostic {
+                    severity: DiagnosticSeverity::Error,
+                    line: 6,
+                    column: Some(17),
+                    message: "';' expected".to_string(),
+                },
+            ]
+        );
+    }
+}
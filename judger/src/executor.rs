@@ -1,21 +1,122 @@
 use anyhow::Result;
-use std::{path::PathBuf, sync::Arc};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{HashMap, VecDeque},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
 use tempfile::TempDir;
-use shared::Language;
+use shared::{CompilerDiagnostic, Language};
 
-use crate::{config::Config, sandbox::Sandbox};
+use crate::{config::Config, diagnostics::parse_diagnostics, sandbox::Sandbox};
+
+/// Identifies a compilation: identical source compiled for the same language
+/// with the same command always produces the same artifact.
+type CompileCacheKey = [u8; 32];
+
+fn compile_cache_key(source_code: &str, language_id: uuid::Uuid, compile_command: &str) -> CompileCacheKey {
+    let mut hasher = Sha256::new();
+    // Length-prefix each part so that shifting bytes between them changes the key
+    for part in [source_code.as_bytes(), language_id.as_bytes(), compile_command.as_bytes()] {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+/// A compiled executable in `work_dir`. The file is deleted once neither the
+/// compile cache nor a job running it holds the artifact any more.
+struct CompiledArtifact {
+    path: PathBuf,
+}
+
+impl CompiledArtifact {
+    fn new(path: PathBuf) -> Self {
+        CompiledArtifact { path }
+    }
+}
+
+impl Drop for CompiledArtifact {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                tracing::warn!("Failed to remove compiled artifact {}: {}", self.path.display(), e);
+            }
+        }
+    }
+}
+
+/// Artifacts of recent successful compilations, evicting the least recently
+/// used entry once `capacity` is reached.
+struct CompileCache {
+    capacity: usize,
+    entries: HashMap<CompileCacheKey, Arc<CompiledArtifact>>,
+    recency: VecDeque<CompileCacheKey>,
+}
+
+impl CompileCache {
+    fn new(capacity: usize) -> Self {
+        CompileCache {
+            capacity,
+            entries: HashMap::new(),
+            recency: VecDeque::new(),
+        }
+    }
+
+    fn get(&mut self, key: &CompileCacheKey) -> Option<Arc<CompiledArtifact>> {
+        let artifact = self.entries.get(key)?.clone();
+        self.touch(key);
+        Some(artifact)
+    }
+
+    fn insert(&mut self, key: CompileCacheKey, artifact: Arc<CompiledArtifact>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.entries.insert(key, artifact).is_some() {
+            self.touch(&key);
+            return;
+        }
+
+        self.recency.push_back(key);
+        while self.entries.len() > self.capacity {
+            if let Some(oldest) = self.recency.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+
+    fn remove(&mut self, key: &CompileCacheKey) {
+        if self.entries.remove(key).is_some() {
+            self.recency.retain(|k| k != key);
+        }
+    }
+
+    fn touch(&mut self, key: &CompileCacheKey) {
+        if let Some(index) = self.recency.iter().position(|k| k == key) {
+            self.recency.remove(index);
+        }
+        self.recency.push_back(*key);
+    }
+}
 
 #[derive(Clone)]
 pub struct Executor {
     config: Arc<Config>,
     sandbox: Sandbox,
+    compile_cache: Arc<Mutex<CompileCache>>,
 }
 
 pub struct CompileResult {
     pub success: bool,
     pub executable_path: PathBuf,
-    #[allow(dead_code)]
+    /// Keeps a compiled executable on disk while the result is in use, even
+    /// if the cache evicts it meanwhile.
+    _artifact: Option<Arc<CompiledArtifact>>,
     pub error_message: Option<String>,
+    pub diagnostics: Vec<CompilerDiagnostic>,
+    pub cache_hit: bool,
 }
 
 pub struct RunResult {
@@ -31,8 +132,9 @@ impl Executor {
         let sandbox = Sandbox::new()?;
         
         Ok(Executor {
-            config,
             sandbox,
+            compile_cache: Arc::new(Mutex::new(CompileCache::new(config.compile_cache_capacity))),
+            config,
         })
     }
 
@@ -50,10 +152,32 @@ impl Executor {
             return Ok(CompileResult {
                 success: true,
                 executable_path: source_file,
+                _artifact: None,
                 error_message: None,
+                diagnostics: Vec::new(),
+                cache_hit: false,
             });
         };
 
+        // Reuse a previous artifact for identical source and compile command,
+        // e.g. during rejudges
+        let cache_key = compile_cache_key(source_code, language.id, compile_command);
+        let cached = self.compile_cache.lock().unwrap().get(&cache_key);
+        if let Some(cached) = cached {
+            if cached.path.exists() {
+                tracing::debug!("Compile cache hit for {}", language.name);
+                return Ok(CompileResult {
+                    success: true,
+                    executable_path: cached.path.clone(),
+                    _artifact: Some(cached),
+                    error_message: None,
+                    diagnostics: Vec::new(),
+                    cache_hit: true,
+                });
+            }
+            self.compile_cache.lock().unwrap().remove(&cache_key);
+        }
+
         // Replace placeholders in compile command
         let compile_cmd = compile_command
             .replace("solution.cpp", source_file.to_str().unwrap())
@@ -81,23 +205,35 @@ impl Executor {
                 tokio::fs::copy(&source_file, &persistent_path).await?;
             }
 
+            let artifact = Arc::new(CompiledArtifact::new(persistent_path.clone()));
+            self.compile_cache
+                .lock()
+                .unwrap()
+                .insert(cache_key, artifact.clone());
+
             Ok(CompileResult {
                 success: true,
                 executable_path: persistent_path,
+                _artifact: Some(artifact),
                 error_message: None,
+                diagnostics: Vec::new(),
+                cache_hit: false,
             })
         } else {
             Ok(CompileResult {
                 success: false,
                 executable_path: PathBuf::new(),
+                _artifact: None,
+                diagnostics: parse_diagnostics(language, &result.stderr),
                 error_message: Some(result.stderr),
+                cache_hit: false,
             })
         }
     }
 
     pub async fn run(
         &self,
-        executable_path: &PathBuf,
+        executable_path: &Path,
         input_data: &str,
         time_limit_ms: i32,
         memory_limit_kb: i32,
@@ -131,4 +267,127 @@ impl Executor {
             memory_kb: result.memory_kb,
         })
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_executor;
+
+    fn language(compile_command: &str) -> Language {
+        Language {
+            id: uuid::Uuid::nil(),
+            name: "Test".to_string(),
+            version: "1".to_string(),
+            compile_command: Some(compile_command.to_string()),
+            run_command: "./solution".to_string(),
+            file_extension: "txt".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn identical_compilation_hits_the_cache() {
+        let executor = test_executor();
+        let language = language("true");
+
+        let first = executor.compile("source", &language).await.unwrap();
+        assert!(first.success);
+        assert!(!first.cache_hit);
+
+        let second = executor.compile("source", &language).await.unwrap();
+        assert!(second.cache_hit);
+        assert_eq!(second.executable_path, first.executable_path);
+    }
+
+    #[tokio::test]
+    async fn different_source_misses_the_cache() {
+        let executor = test_executor();
+        let language = language("true");
+
+        executor.compile("source a", &language).await.unwrap();
+        let other = executor.compile("source b", &language).await.unwrap();
+        assert!(!other.cache_hit);
+    }
+
+    #[tokio::test]
+    async fn compile_command_change_invalidates_the_cache() {
+        let executor = test_executor();
+
+        executor.compile("source", &language("true")).await.unwrap();
+        let recompiled = executor.compile("source", &language("true --changed")).await.unwrap();
+        assert!(!recompiled.cache_hit);
+    }
+
+    #[test]
+    fn cache_key_depends_on_every_part() {
+        let id = uuid::Uuid::nil();
+        let key = compile_cache_key("ab", id, "c");
+        assert_eq!(key, compile_cache_key("ab", id, "c"));
+        assert_ne!(key, compile_cache_key("a", id, "bc"));
+        assert_ne!(key, compile_cache_key("ab", uuid::Uuid::from_u128(1), "c"));
+    }
+
+    fn artifact(dir: &TempDir, name: &str) -> Arc<CompiledArtifact> {
+        let path = dir.path().join(name);
+        std::fs::write(&path, name).unwrap();
+        Arc::new(CompiledArtifact::new(path))
+    }
+
+    fn cached_path(cache: &mut CompileCache, key: &CompileCacheKey) -> Option<PathBuf> {
+        cache.get(key).map(|artifact| artifact.path.clone())
+    }
+
+    #[test]
+    fn cache_evicts_the_least_recently_used_entry() {
+        let dir = TempDir::new().unwrap();
+        let mut cache = CompileCache::new(2);
+        let [a, b, c] = [[1u8; 32], [2u8; 32], [3u8; 32]];
+
+        cache.insert(a, artifact(&dir, "a"));
+        cache.insert(b, artifact(&dir, "b"));
+        assert_eq!(cached_path(&mut cache, &a), Some(dir.path().join("a")));
+
+        cache.insert(c, artifact(&dir, "c"));
+        assert_eq!(cached_path(&mut cache, &b), None);
+        assert_eq!(cached_path(&mut cache, &a), Some(dir.path().join("a")));
+        assert_eq!(cached_path(&mut cache, &c), Some(dir.path().join("c")));
+    }
+
+    #[test]
+    fn evicted_and_removed_artifacts_are_deleted() {
+        let dir = TempDir::new().unwrap();
+        let mut cache = CompileCache::new(1);
+        let [a, b] = [[1u8; 32], [2u8; 32]];
+
+        cache.insert(a, artifact(&dir, "a"));
+        cache.insert(b, artifact(&dir, "b"));
+        assert!(!dir.path().join("a").exists());
+
+        cache.remove(&b);
+        assert!(!dir.path().join("b").exists());
+    }
+
+    #[test]
+    fn evicted_artifact_survives_while_a_job_holds_it() {
+        let dir = TempDir::new().unwrap();
+        let mut cache = CompileCache::new(1);
+        let [a, b] = [[1u8; 32], [2u8; 32]];
+
+        cache.insert(a, artifact(&dir, "a"));
+        let in_use = cache.get(&a).unwrap();
+        cache.insert(b, artifact(&dir, "b"));
+        assert!(in_use.path.exists());
+
+        drop(in_use);
+        assert!(!dir.path().join("a").exists());
+    }
+
+    #[test]
+    fn zero_capacity_cache_stores_nothing() {
+        let dir = TempDir::new().unwrap();
+        let mut cache = CompileCache::new(0);
+        cache.insert([1u8; 32], artifact(&dir, "a"));
+        assert_eq!(cached_path(&mut cache, &[1u8; 32]), None);
+        assert!(!dir.path().join("a").exists());
+    }
+}
@@ -1,8 +1,12 @@
+mod checker;
 mod config;
 mod coordinator;
 mod database;
+mod diagnostics;
 mod executor;
 mod sandbox;
+#[cfg(test)]
+mod test_support;
 
 use anyhow::Result;
 use std::sync::Arc;
@@ -0,0 +1,128 @@
+use shared::*;
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{config::Config, executor::Executor};
+
+pub fn test_executor() -> Executor {
+    let config = Config {
+        database_url: String::new(),
+        rabbitmq_url: String::new(),
+        work_dir: std::env::temp_dir().to_string_lossy().into_owned(),
+        max_concurrent_jobs: 1,
+        judge_node_id: "test-node".to_string(),
+        compile_cache_capacity: 16,
+    };
+    Executor::new(Arc::new(config)).unwrap()
+}
+
+struct ProblemFixture {
+    user_id: Uuid,
+    problem_id: Uuid,
+    language_id: Uuid,
+}
+
+/// Insert a user and a problem of the given question type with one test
+/// case expecting `expected_output`.
+async fn insert_problem(pool: &PgPool, question_type: &str, expected_output: &str) -> ProblemFixture {
+    let user_id: (Uuid,) = sqlx::query_as(
+        "INSERT INTO users (username, email, hashed_password) VALUES ($1, $2, 'x') RETURNING id",
+    )
+    .bind(format!("user-{}", Uuid::new_v4()))
+    .bind(format!("{}@example.com", Uuid::new_v4()))
+    .fetch_one(pool)
+    .await
+    .unwrap();
+
+    let problem_id: (Uuid,) = sqlx::query_as(
+        r#"
+        INSERT INTO problems (title, author_id, statement, difficulty, time_limit_ms, memory_limit_kb, question_type_id)
+        SELECT 'Problem', $1, '', 'easy', 1000, 65536, id FROM question_types WHERE name = $2
+        RETURNING id
+        "#,
+    )
+    .bind(user_id.0)
+    .bind(question_type)
+    .fetch_one(pool)
+    .await
+    .unwrap();
+
+    sqlx::query(
+        "INSERT INTO test_cases (problem_id, input_data, output_data, order_index) VALUES ($1, '', $2, 0)",
+    )
+    .bind(problem_id.0)
+    .bind(expected_output)
+    .execute(pool)
+    .await
+    .unwrap();
+
+    let language_id: (Uuid,) = sqlx::query_as("SELECT id FROM languages WHERE name = 'Python 3'")
+        .fetch_one(pool)
+        .await
+        .unwrap();
+
+    ProblemFixture {
+        user_id: user_id.0,
+        problem_id: problem_id.0,
+        language_id: language_id.0,
+    }
+}
+
+fn job(fixture: &ProblemFixture, id: Uuid, source_code: &str, dry_run: bool) -> JudgingJob {
+    JudgingJob {
+        submission_id: id,
+        user_id: fixture.user_id,
+        problem_id: fixture.problem_id,
+        language_id: fixture.language_id,
+        source_code: source_code.to_string(),
+        phase: JudgingPhase::SystemTest,
+        dry_run,
+    }
+}
+
+/// Insert a problem as in [`insert_problem`] and a queued submission to it,
+/// returning the submission's judging job.
+pub async fn insert_submission(pool: &PgPool, question_type: &str, expected_output: &str, source_code: &str) -> JudgingJob {
+    let fixture = insert_problem(pool, question_type, expected_output).await;
+
+    let submission_id: (Uuid,) = sqlx::query_as(
+        r#"
+        INSERT INTO submissions (user_id, problem_id, language_id, source_code)
+        VALUES ($1, $2, $3, $4)
+        RETURNING id
+        "#,
+    )
+    .bind(fixture.user_id)
+    .bind(fixture.problem_id)
+    .bind(fixture.language_id)
+    .bind(source_code)
+    .fetch_one(pool)
+    .await
+    .unwrap();
+
+    job(&fixture, submission_id.0, source_code, false)
+}
+
+/// Insert a problem as in [`insert_problem`] and a queued dry run of it,
+/// returning the dry run's judging job.
+pub async fn insert_dry_run(pool: &PgPool, question_type: &str, expected_output: &str, source_code: &str) -> JudgingJob {
+    let fixture = insert_problem(pool, question_type, expected_output).await;
+
+    let dry_run_id: (Uuid,) = sqlx::query_as(
+        r#"
+        INSERT INTO dry_runs (problem_id, language_id, requested_by, source_code)
+        VALUES ($1, $2, $3, $4)
+        RETURNING id
+        "#,
+    )
+    .bind(fixture.problem_id)
+    .bind(fixture.language_id)
+    .bind(fixture.user_id)
+    .bind(source_code)
+    .fetch_one(pool)
+    .await
+    .unwrap();
+
+    job(&fixture, dry_run_id.0, source_code, true)
+}
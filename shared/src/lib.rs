@@ -1,5 +1,6 @@
 pub mod models;
 pub mod types;
+pub mod utils;
 
 pub use models::*;
 pub use types::*;
\ No newline at end of file
@@ -28,6 +28,49 @@ pub struct Problem {
     pub contest_id: Option<Uuid>,
 }
 
+impl Problem {
+    /// Key in `metadata` holding the list of language names or ids a problem
+    /// accepts. Absent or empty means every language is allowed.
+    pub const ALLOWED_LANGUAGES_KEY: &'static str = "allowed_languages";
+
+    pub fn allowed_languages(&self) -> Option<Vec<String>> {
+        let languages: Vec<String> = self
+            .metadata
+            .get(Self::ALLOWED_LANGUAGES_KEY)?
+            .as_array()?
+            .iter()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect();
+
+        if languages.is_empty() {
+            None
+        } else {
+            Some(languages)
+        }
+    }
+
+    pub fn allows_language(&self, language: &Language) -> bool {
+        match self.allowed_languages() {
+            Some(allowed) => allowed.iter().any(|entry| {
+                entry.eq_ignore_ascii_case(&language.name) || *entry == language.id.to_string()
+            }),
+            None => true,
+        }
+    }
+
+    /// Explanation recorded on a submission rejected by [`Problem::allows_language`].
+    pub fn disallowed_language_message(&self, language: &Language) -> String {
+        match self.allowed_languages() {
+            Some(allowed) => format!(
+                "{} is not allowed for this problem. Allowed languages: {}",
+                language.name,
+                allowed.join(", ")
+            ),
+            None => format!("{} is not allowed for this problem", language.name),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct QuestionTypeModel {
     pub id: Uuid,
@@ -58,6 +101,13 @@ pub struct Submission {
     pub execution_time_ms: Option<i32>,
     pub execution_memory_kb: Option<i32>,
     pub contest_id: Option<Uuid>,
+    pub judging_phase: Option<String>, // Maps to JudgingPhase
+    pub judge_node_id: Option<String>,
+    pub judging_started_at: Option<DateTime<Utc>>,
+    pub judged_at: Option<DateTime<Utc>>,
+    pub judge_log: Option<String>,
+    pub compile_diagnostics: serde_json::Value, // Vec<CompilerDiagnostic>
+    pub compile_log: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -67,6 +117,7 @@ pub struct TestCase {
     pub input_data: String,
     pub output_data: String,
     pub is_sample: bool,
+    pub is_pretest: bool,
     pub order_index: i32,
 }
 
@@ -82,6 +133,25 @@ pub struct SubmissionResult {
     pub stderr: Option<String>,
 }
 
+/// An admin-requested judging run of arbitrary source against a problem,
+/// kept apart from submissions.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct DryRun {
+    pub id: Uuid,
+    pub problem_id: Uuid,
+    pub language_id: Uuid,
+    pub requested_by: Uuid,
+    pub source_code: String,
+    pub status: String, // Maps to JudgeStatus
+    pub verdict: Option<String>, // Maps to Verdict
+    pub execution_time_ms: Option<i32>,
+    pub execution_memory_kb: Option<i32>,
+    pub test_results: serde_json::Value, // Vec<TestCaseResult>
+    pub created_at: DateTime<Utc>,
+    pub judged_at: Option<DateTime<Utc>>,
+    pub judge_log: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Contest {
     pub id: Uuid,
@@ -94,6 +164,23 @@ pub struct Contest {
     pub participant_count: Option<i32>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ProblemSubmissionStats {
+    pub problem_id: Uuid,
+    pub solve_count: i64, // distinct users with an accepted submission
+    pub attempt_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct AuditLogEntry {
+    pub id: Uuid,
+    pub action: String, // e.g., "contest.create", "contest_admin.assign"
+    pub actor_id: Uuid,
+    pub target_id: Option<Uuid>,
+    pub details: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct ContestAdmin {
     pub id: Uuid,
@@ -156,6 +243,23 @@ pub struct SubmissionResponse {
     pub submission_id: Uuid,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct DryRunRequest {
+    pub language_id: Uuid,
+    pub source_code: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DryRunResponse {
+    pub dry_run_id: Uuid,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QueuePositionResponse {
+    pub position: i64, // queued submissions ahead of this one
+    pub eta_seconds: Option<f64>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct CreateContestRequest {
     pub title: String,
@@ -164,6 +268,16 @@ pub struct CreateContestRequest {
     pub duration: i32,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ExtendContestRequest {
+    pub new_end_time: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SystemTestResponse {
+    pub queued_submissions: usize,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct CreateProblemRequest {
     pub title: String,
@@ -177,6 +291,47 @@ pub struct CreateProblemRequest {
     pub contest_id: Option<Uuid>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct ContestProblemSummary {
+    #[serde(flatten)]
+    pub problem: Problem,
+    pub solve_count: i64,
+    pub attempt_count: i64,
+    /// Live estimate in `(0, 1)`, higher meaning harder. Unlike the
+    /// author-assigned `difficulty` it follows how the contest is going.
+    pub estimated_difficulty: f64,
+}
+
+impl ContestProblemSummary {
+    pub fn new(problem: Problem, solve_count: i64, attempt_count: i64) -> Self {
+        ContestProblemSummary {
+            problem,
+            solve_count,
+            attempt_count,
+            estimated_difficulty: Self::estimate_difficulty(solve_count, attempt_count),
+        }
+    }
+
+    /// Share of attempts that did not produce a new solver, smoothed with one
+    /// pseudo-solve and one pseudo-failure so that a problem nobody has tried
+    /// yet sits at 0.5 and a few early attempts do not swing it to 0 or 1.
+    pub fn estimate_difficulty(solve_count: i64, attempt_count: i64) -> f64 {
+        1.0 - (solve_count as f64 + 1.0) / (attempt_count as f64 + 2.0)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubmissionHistogramBucket {
+    pub start_time: DateTime<Utc>,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubmissionHistogramResponse {
+    pub bucket_seconds: i64,
+    pub buckets: Vec<SubmissionHistogramBucket>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct AssignContestAdminRequest {
     pub contest_id: Uuid,
@@ -202,4 +357,83 @@ pub struct ContestAdminWithUser {
     pub assigned_at: DateTime<Utc>,
     pub username: String,
     pub email: String,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn problem(metadata: serde_json::Value) -> Problem {
+        Problem {
+            id: Uuid::nil(),
+            title: "A + B".to_string(),
+            author_id: Uuid::nil(),
+            created_at: Utc::now(),
+            statement: String::new(),
+            difficulty: "easy".to_string(),
+            time_limit_ms: 1000,
+            memory_limit_kb: 65536,
+            question_type_id: Uuid::nil(),
+            metadata,
+            points: 100,
+            contest_id: None,
+        }
+    }
+
+    fn language(name: &str) -> Language {
+        Language {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            version: "1".to_string(),
+            compile_command: None,
+            run_command: String::new(),
+            file_extension: String::new(),
+        }
+    }
+
+    #[test]
+    fn low_solve_rate_estimates_a_higher_difficulty() {
+        let hard = ContestProblemSummary::estimate_difficulty(1, 20);
+        let easy = ContestProblemSummary::estimate_difficulty(15, 20);
+        assert!(hard > easy, "{} should exceed {}", hard, easy);
+    }
+
+    #[test]
+    fn untried_problem_has_a_neutral_difficulty() {
+        assert_eq!(ContestProblemSummary::estimate_difficulty(0, 0), 0.5);
+    }
+
+    #[test]
+    fn allows_listed_language_by_name_or_id() {
+        let python = language("Python 3");
+        let java = language("Java");
+        let problem = problem(serde_json::json!({
+            "allowed_languages": ["python 3", java.id.to_string()],
+        }));
+
+        assert!(problem.allows_language(&python));
+        assert!(problem.allows_language(&java));
+    }
+
+    #[test]
+    fn rejects_unlisted_language() {
+        let problem = problem(serde_json::json!({"allowed_languages": ["C++17"]}));
+
+        assert!(!problem.allows_language(&language("Python 3")));
+        assert_eq!(
+            problem.disallowed_language_message(&language("Python 3")),
+            "Python 3 is not allowed for this problem. Allowed languages: C++17"
+        );
+    }
+
+    #[test]
+    fn unrestricted_problem_allows_every_language() {
+        for metadata in [
+            serde_json::json!({}),
+            serde_json::json!({"allowed_languages": []}),
+            serde_json::json!({"allowed_languages": null}),
+        ] {
+            assert!(problem(metadata).allows_language(&language("Brainfuck")));
+        }
+    }
+}
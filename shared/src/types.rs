@@ -1,7 +1,8 @@
 use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum JudgeStatus {
     Queued,
     Compiling,
@@ -10,7 +11,31 @@ pub enum JudgeStatus {
     Error,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl JudgeStatus {
+    /// The representation stored in `submissions.status`.
+    pub fn as_db_str(&self) -> &'static str {
+        match self {
+            JudgeStatus::Queued => "Queued",
+            JudgeStatus::Compiling => "Compiling",
+            JudgeStatus::Running => "Running",
+            JudgeStatus::Finished => "Finished",
+            JudgeStatus::Error => "Error",
+        }
+    }
+
+    pub fn from_db_str(s: &str) -> Option<Self> {
+        match s {
+            "Queued" => Some(JudgeStatus::Queued),
+            "Compiling" => Some(JudgeStatus::Compiling),
+            "Running" => Some(JudgeStatus::Running),
+            "Finished" => Some(JudgeStatus::Finished),
+            "Error" => Some(JudgeStatus::Error),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Verdict {
     Accepted,
     WrongAnswer,
@@ -22,6 +47,107 @@ pub enum Verdict {
     SystemError,
 }
 
+impl fmt::Display for Verdict {
+    /// Short verdict code, e.g. `AC` or `TLE`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let code = match self {
+            Verdict::Accepted => "AC",
+            Verdict::WrongAnswer => "WA",
+            Verdict::TimeLimitExceeded => "TLE",
+            Verdict::MemoryLimitExceeded => "MLE",
+            Verdict::RuntimeError => "RE",
+            Verdict::CompilationError => "CE",
+            Verdict::PresentationError => "PE",
+            Verdict::SystemError => "SE",
+        };
+        f.write_str(code)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseVerdictError(pub String);
+
+impl fmt::Display for ParseVerdictError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown verdict: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseVerdictError {}
+
+impl FromStr for Verdict {
+    type Err = ParseVerdictError;
+
+    /// Parses the short code (`WA`) or the long form in any case and with any
+    /// spacing (`Wrong Answer`, `WrongAnswer`, `wrong_answer`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized: String = s
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '_' && *c != '-')
+            .collect::<String>()
+            .to_ascii_lowercase();
+
+        match normalized.as_str() {
+            "ac" | "accepted" => Ok(Verdict::Accepted),
+            "wa" | "wronganswer" => Ok(Verdict::WrongAnswer),
+            "tle" | "timelimitexceeded" => Ok(Verdict::TimeLimitExceeded),
+            "mle" | "memorylimitexceeded" => Ok(Verdict::MemoryLimitExceeded),
+            "re" | "runtimeerror" => Ok(Verdict::RuntimeError),
+            "ce" | "compilationerror" => Ok(Verdict::CompilationError),
+            "pe" | "presentationerror" => Ok(Verdict::PresentationError),
+            "se" | "systemerror" => Ok(Verdict::SystemError),
+            _ => Err(ParseVerdictError(s.to_string())),
+        }
+    }
+}
+
+/// Extra verdict strings that judges commonly emit for an accepted solution,
+/// on top of the forms [`Verdict::from_str`] already parses as `Accepted`.
+pub const DEFAULT_ACCEPTED_VERDICT_ALIASES: &[&str] = &["OK", "CORRECT"];
+
+impl Verdict {
+    /// The representation stored in `submissions.verdict` and
+    /// `submission_results.verdict`.
+    pub fn as_db_str(&self) -> &'static str {
+        match self {
+            Verdict::Accepted => "Accepted",
+            Verdict::WrongAnswer => "WrongAnswer",
+            Verdict::TimeLimitExceeded => "TimeLimitExceeded",
+            Verdict::MemoryLimitExceeded => "MemoryLimitExceeded",
+            Verdict::RuntimeError => "RuntimeError",
+            Verdict::CompilationError => "CompilationError",
+            Verdict::PresentationError => "PresentationError",
+            Verdict::SystemError => "SystemError",
+        }
+    }
+
+    /// Parses a stored verdict; any form accepted by [`Verdict::from_str`]
+    /// is recognized.
+    pub fn from_db_str(s: &str) -> Option<Self> {
+        s.parse().ok()
+    }
+
+    /// Whether a stored or judge-emitted verdict string means accepted,
+    /// using the default alias set.
+    pub fn is_accepted(verdict: &str) -> bool {
+        Self::is_accepted_with_aliases(verdict, DEFAULT_ACCEPTED_VERDICT_ALIASES)
+    }
+
+    /// Same as [`Verdict::is_accepted`] but with a caller-provided alias set,
+    /// for deployments whose judges report acceptance differently. Anything
+    /// that parses as [`Verdict::Accepted`] is always accepted.
+    pub fn is_accepted_with_aliases<S: AsRef<str>>(verdict: &str, aliases: &[S]) -> bool {
+        if let Ok(parsed) = verdict.parse::<Verdict>() {
+            return matches!(parsed, Verdict::Accepted);
+        }
+
+        let verdict = verdict.trim();
+        aliases
+            .iter()
+            .any(|alias| alias.as_ref().eq_ignore_ascii_case(verdict))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum QuestionType {
     IoiStandard,
@@ -29,6 +155,84 @@ pub enum QuestionType {
     Interactive,
 }
 
+/// How a contestant's output is compared against the expected output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum ComparisonMode {
+    /// Byte-for-byte equality.
+    Exact,
+    /// Equality after trimming leading/trailing whitespace.
+    #[default]
+    Trimmed,
+    /// Equality of whitespace-separated tokens.
+    IgnoreWhitespace,
+    /// Token-wise comparison where numeric tokens are accepted within an
+    /// absolute or relative tolerance of the expected value.
+    FloatingPoint { abs_tol: f64, rel_tol: f64 },
+}
+
+/// Per-problem output comparison settings, stored under the `comparison`
+/// key of `problems.metadata`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ComparisonConfig {
+    #[serde(default)]
+    pub mode: ComparisonMode,
+    /// Report output that only differs in whitespace as WrongAnswer instead
+    /// of PresentationError in the Exact and Trimmed modes.
+    #[serde(default)]
+    pub treat_pe_as_wa: bool,
+}
+
+impl ComparisonConfig {
+    pub const METADATA_KEY: &'static str = "comparison";
+
+    /// Read the config from a problem's metadata, defaulting when absent.
+    pub fn from_problem_metadata(metadata: &serde_json::Value) -> Result<Self, serde_json::Error> {
+        match metadata.get(Self::METADATA_KEY) {
+            Some(value) => serde_json::from_value(value.clone()),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let ComparisonMode::FloatingPoint { abs_tol, rel_tol } = self.mode {
+            if !abs_tol.is_finite() || abs_tol < 0.0 {
+                return Err("abs_tol must be a finite, non-negative number".to_string());
+            }
+            if !rel_tol.is_finite() || rel_tol < 0.0 {
+                return Err("rel_tol must be a finite, non-negative number".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Which test set a submission is judged against. Pretests run while the
+/// contest is live; system tests run the full set.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum JudgingPhase {
+    Pretest,
+    #[default]
+    SystemTest,
+}
+
+impl JudgingPhase {
+    /// The representation stored in `submissions.judging_phase`.
+    pub fn as_db_str(&self) -> &'static str {
+        match self {
+            JudgingPhase::Pretest => "Pretest",
+            JudgingPhase::SystemTest => "SystemTest",
+        }
+    }
+
+    pub fn from_db_str(s: &str) -> Option<Self> {
+        match s {
+            "Pretest" => Some(JudgingPhase::Pretest),
+            "SystemTest" => Some(JudgingPhase::SystemTest),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JudgingJob {
     pub submission_id: Uuid,
@@ -36,6 +240,30 @@ pub struct JudgingJob {
     pub problem_id: Uuid,
     pub language_id: Uuid,
     pub source_code: String,
+    #[serde(default)]
+    pub phase: JudgingPhase,
+    /// Judge without touching submissions; `submission_id` is then the id of
+    /// the `dry_runs` row that receives the outcome.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+    Note,
+}
+
+/// A single compiler message located in the submitted source. Notes and
+/// continuation lines are folded into the message of the diagnostic they
+/// belong to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompilerDiagnostic {
+    pub severity: DiagnosticSeverity,
+    pub line: u32,
+    pub column: Option<u32>,
+    pub message: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -65,4 +293,105 @@ pub enum WebSocketMessage {
         execution_time_ms: Option<i32>,
         execution_memory_kb: Option<i32>,
     },
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_VERDICTS: [Verdict; 8] = [
+        Verdict::Accepted,
+        Verdict::WrongAnswer,
+        Verdict::TimeLimitExceeded,
+        Verdict::MemoryLimitExceeded,
+        Verdict::RuntimeError,
+        Verdict::CompilationError,
+        Verdict::PresentationError,
+        Verdict::SystemError,
+    ];
+
+    #[test]
+    fn judge_status_db_str_round_trips() {
+        for status in [
+            JudgeStatus::Queued,
+            JudgeStatus::Compiling,
+            JudgeStatus::Running,
+            JudgeStatus::Finished,
+            JudgeStatus::Error,
+        ] {
+            assert_eq!(JudgeStatus::from_db_str(status.as_db_str()), Some(status));
+        }
+        assert_eq!(JudgeStatus::from_db_str("Pending"), None);
+    }
+
+    #[test]
+    fn verdict_db_str_round_trips() {
+        for verdict in ALL_VERDICTS {
+            assert_eq!(Verdict::from_db_str(verdict.as_db_str()), Some(verdict));
+        }
+        assert_eq!(Verdict::from_db_str("Skipped"), None);
+    }
+
+    #[test]
+    fn verdict_parses_every_accepted_form() {
+        let cases = [
+            (&["AC", "ac", "Accepted", "accepted", "ACCEPTED"][..], Verdict::Accepted),
+            (&["WA", "wa", "WrongAnswer", "Wrong Answer", "wrong_answer", "wrong-answer"][..], Verdict::WrongAnswer),
+            (&["TLE", "TimeLimitExceeded", "Time Limit Exceeded", "time_limit_exceeded"][..], Verdict::TimeLimitExceeded),
+            (&["MLE", "MemoryLimitExceeded", "Memory Limit Exceeded", "memory_limit_exceeded"][..], Verdict::MemoryLimitExceeded),
+            (&["RE", "RuntimeError", "Runtime Error", "runtime_error"][..], Verdict::RuntimeError),
+            (&["CE", "CompilationError", "Compilation Error", "compilation_error"][..], Verdict::CompilationError),
+            (&["PE", "PresentationError", "Presentation Error", "presentation_error"][..], Verdict::PresentationError),
+            (&["SE", "SystemError", "System Error", "system_error"][..], Verdict::SystemError),
+        ];
+
+        for (forms, verdict) in cases {
+            for form in forms {
+                assert_eq!(form.parse::<Verdict>(), Ok(verdict.clone()), "parsing {:?}", form);
+            }
+        }
+    }
+
+    #[test]
+    fn verdict_display_code_parses_back() {
+        for verdict in ALL_VERDICTS {
+            assert_eq!(verdict.to_string().parse::<Verdict>(), Ok(verdict));
+        }
+    }
+
+    #[test]
+    fn verdict_rejects_unknown_strings() {
+        for input in ["", "OK", "Accept", "W A X"] {
+            assert_eq!(input.parse::<Verdict>(), Err(ParseVerdictError(input.to_string())));
+        }
+    }
+
+    #[test]
+    fn judging_phase_db_str_round_trips() {
+        for phase in [JudgingPhase::Pretest, JudgingPhase::SystemTest] {
+            assert_eq!(JudgingPhase::from_db_str(phase.as_db_str()), Some(phase));
+        }
+        assert_eq!(JudgingPhase::from_db_str("Final"), None);
+    }
+
+    #[test]
+    fn is_accepted_recognizes_aliases_and_rejects_unknown_strings() {
+        assert!(Verdict::is_accepted("Accepted"));
+        assert!(Verdict::is_accepted("ac"));
+        assert!(Verdict::is_accepted("OK"));
+        assert!(Verdict::is_accepted(" correct "));
+
+        assert!(!Verdict::is_accepted("WrongAnswer"));
+        assert!(!Verdict::is_accepted("definitely not a verdict"));
+        assert!(!Verdict::is_accepted(""));
+    }
+
+    #[test]
+    fn is_accepted_with_aliases_uses_only_the_given_extra_aliases() {
+        assert!(Verdict::is_accepted_with_aliases("PASSED", &["passed"]));
+        assert!(Verdict::is_accepted_with_aliases("Accepted", &[] as &[&str]));
+        assert!(!Verdict::is_accepted_with_aliases("OK", &["PASSED"]));
+        // An alias can never turn a recognized non-accepted verdict into AC
+        assert!(!Verdict::is_accepted_with_aliases("WA", &["WA"]));
+    }
+}
@@ -0,0 +1,62 @@
+const ELLIPSIS: &str = "…";
+
+/// Render at most `max` bytes of possibly non-UTF-8 output as a string.
+/// Invalid sequences become U+FFFD, truncation never splits a character, and
+/// a trailing ellipsis, counted within `max`, marks truncated output.
+pub fn safe_preview(bytes: &[u8], max: usize) -> String {
+    let text = String::from_utf8_lossy(bytes);
+    if text.len() <= max {
+        return text.into_owned();
+    }
+
+    // Too small a budget for the ellipsis just truncates
+    let ellipsis = if max >= ELLIPSIS.len() { ELLIPSIS } else { "" };
+    let mut end = max - ellipsis.len();
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}{}", &text[..end], ellipsis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_within_the_limit_is_unchanged() {
+        assert_eq!(safe_preview(b"hello", 5), "hello");
+        assert_eq!(safe_preview(b"", 0), "");
+    }
+
+    #[test]
+    fn truncated_output_including_ellipsis_fits_the_limit() {
+        let preview = safe_preview(b"hello world", 8);
+        assert_eq!(preview, "hello…");
+        assert!(preview.len() <= 8);
+    }
+
+    #[test]
+    fn truncation_does_not_split_a_multibyte_character() {
+        // "é" occupies bytes 2..4; a 6-byte limit leaves 3 bytes before the
+        // ellipsis, one byte past the start of "é"
+        let preview = safe_preview("abécde".as_bytes(), 6);
+        assert_eq!(preview, "ab…");
+        assert!(preview.len() <= 6);
+    }
+
+    #[test]
+    fn invalid_bytes_become_replacement_characters() {
+        assert_eq!(safe_preview(b"ok\xff\xfe", 64), "ok\u{FFFD}\u{FFFD}");
+
+        // U+FFFD is three bytes, so it is dropped rather than split
+        let preview = safe_preview(b"ok\xffmore", 7);
+        assert_eq!(preview, "ok…");
+        assert!(preview.len() <= 7);
+    }
+
+    #[test]
+    fn limit_smaller_than_the_ellipsis_truncates_without_it() {
+        assert_eq!(safe_preview(b"hello", 2), "he");
+        assert_eq!(safe_preview("é".as_bytes(), 1), "");
+    }
+}